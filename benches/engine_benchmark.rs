@@ -16,14 +16,15 @@ fn get_serialized_data() -> Vec<u8> {
     let mut rng = StdRng::from_seed([0; 32]);
     let documents: Vec<Document> = (0..NUM_VECTORS)
         .map(|i| {
-            let vector: Vec<f32> = (0..DIMENSION).map(|_| rng.random::<f32>()).collect();
-            Document { id: i as u64, vector }
+            let vector: Vec<f32> = (0..DIMENSION).map(|_| rng.r#gen::<f32>()).collect();
+            Document::new(i as u64, vector)
         })
         .collect();
     
     let state = EngineState {
         format_version: 1,
         documents,
+        ..Default::default()
     };
 
     let mut buf = Vec::new();
@@ -51,7 +52,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         let new_doc_id = (NUM_VECTORS + 1) as u64;
         let new_vector: Vec<f32> = {
             let mut rng = StdRng::from_seed([1; 32]);
-            (0..DIMENSION).map(|_| rng.random::<f32>()).collect()
+            (0..DIMENSION).map(|_| rng.r#gen::<f32>()).collect()
         };
         
         // 매 측정마다 1000개의 문서가 있는 엔진을 새로 준비
@@ -74,7 +75,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         let mut engine = VectorEngine::load_from_bytes(&bytes, DIMENSION).unwrap();
         let query_vector: Vec<f32> = {
             let mut rng = StdRng::from_seed([2; 32]);
-            (0..DIMENSION).map(|_| rng.random()).collect()
+            (0..DIMENSION).map(|_| rng.r#gen()).collect()
         };
         
         // 준비된 엔진에서 search 메서드만 반복 측정
@@ -0,0 +1,173 @@
+use crate::models::{
+    document::{CollectionState, DatabaseState},
+    engine::VectorEngine,
+    errors::VectorEngineError,
+    point::Metric,
+    record::FederatedHit,
+};
+use prost::Message;
+use std::collections::{HashMap, HashSet};
+
+/// 이름 있는 컬렉션(독립적인 VectorEngine)들을 관리하는 데이터베이스
+///
+/// 컬렉션마다 차원과 거리 계산 방식을 따로 가지며, 스냅샷은 모든 컬렉션을 하나로 묶어 저장합니다.
+#[derive(Default)]
+pub struct Database {
+    collections: HashMap<String, VectorEngine>,
+}
+
+impl Database {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn collection_not_found(name: &str) -> VectorEngineError {
+        VectorEngineError::ItemNotFound(format!("컬렉션 '{}'이 존재하지 않습니다.", name))
+    }
+
+    fn collection_exists(name: &str) -> VectorEngineError {
+        VectorEngineError::AlreadyExists(format!("컬렉션 '{}'이 이미 존재합니다.", name))
+    }
+
+    /// 새 컬렉션을 만듭니다. 같은 이름의 컬렉션이 있으면 AlreadyExists를 반환합니다.
+    pub fn create_collection(&mut self, name: &str, dimension: usize, metric: Metric) -> Result<&mut VectorEngine, VectorEngineError> {
+        if self.collections.contains_key(name) {
+            return Err(Self::collection_exists(name));
+        }
+        Ok(self
+            .collections
+            .entry(name.to_string())
+            .or_insert_with(|| VectorEngine::with_metric(dimension, metric)))
+    }
+
+    /// 컬렉션과 그 안의 모든 문서를 삭제합니다.
+    pub fn drop_collection(&mut self, name: &str) -> Result<(), VectorEngineError> {
+        self.collections
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| Self::collection_not_found(name))
+    }
+
+    /// 컬렉션 이름을 바꿉니다. 대상 이름이 이미 쓰이고 있으면 AlreadyExists를 반환합니다.
+    pub fn rename_collection(&mut self, old_name: &str, new_name: &str) -> Result<(), VectorEngineError> {
+        if old_name == new_name {
+            return self.collection(old_name).map(|_| ());
+        }
+        if self.collections.contains_key(new_name) {
+            return Err(Self::collection_exists(new_name));
+        }
+        let engine = self
+            .collections
+            .remove(old_name)
+            .ok_or_else(|| Self::collection_not_found(old_name))?;
+        self.collections.insert(new_name.to_string(), engine);
+        Ok(())
+    }
+
+    /// 컬렉션 이름 목록을 정렬하여 반환합니다.
+    pub fn list_collections(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.collections.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn contains_collection(&self, name: &str) -> bool {
+        self.collections.contains_key(name)
+    }
+
+    pub fn collection(&self, name: &str) -> Result<&VectorEngine, VectorEngineError> {
+        self.collections.get(name).ok_or_else(|| Self::collection_not_found(name))
+    }
+
+    pub fn collection_mut(&mut self, name: &str) -> Result<&mut VectorEngine, VectorEngineError> {
+        self.collections.get_mut(name).ok_or_else(|| Self::collection_not_found(name))
+    }
+
+    /// 여러 컬렉션을 한 번에 검색하여 하나의 순위로 합칩니다.
+    ///
+    /// 각 컬렉션의 거리는 `Metric::similarity`로 같은 척도의 점수로 바꾼 뒤 높은 순으로 정렬하므로,
+    /// 거리 계산 방식이 다른 컬렉션끼리도 비교할 수 있습니다. 대상 컬렉션은 모두 질의와 차원이 같아야 하며,
+    /// 같은 이름이 여러 번 주어져도 한 번만 검색합니다.
+    pub fn federated_search(
+        &self,
+        collections: &[String],
+        query_vector: &[f32],
+        top_k: usize,
+    ) -> Result<Vec<FederatedHit>, VectorEngineError> {
+        // 검색 전에 선택된 컬렉션이 모두 질의와 같은 차원인지 확인
+        for name in collections {
+            let dimension = self.collection(name)?.dimension();
+            if dimension != query_vector.len() {
+                return Err(VectorEngineError::DimensionMismatch(format!(
+                    "컬렉션 '{}'의 차원({})이 쿼리 벡터의 차원({})과 일치하지 않습니다.",
+                    name,
+                    dimension,
+                    query_vector.len()
+                )));
+            }
+        }
+
+        let mut hits: Vec<FederatedHit> = Vec::new();
+        let mut searched: HashSet<&str> = HashSet::new();
+        for name in collections {
+            if !searched.insert(name.as_str()) {
+                continue;
+            }
+            let engine = self.collection(name)?;
+            let metric = engine.metric();
+            let results = engine.search_uncached(query_vector, top_k)?;
+            hits.extend(results.into_iter().map(|(id, distance)| FederatedHit {
+                collection: name.clone(),
+                id,
+                score: metric.similarity(distance),
+            }));
+        }
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.collection.cmp(&b.collection))
+                .then(a.id.cmp(&b.id))
+        });
+        hits.truncate(top_k);
+        Ok(hits)
+    }
+
+    /// 모든 컬렉션을 하나의 DatabaseState 스냅샷으로 직렬화합니다.
+    pub fn save_to_bytes(&self) -> Result<Vec<u8>, VectorEngineError> {
+        let mut collections: Vec<CollectionState> = self
+            .collections
+            .iter()
+            .map(|(name, engine)| CollectionState {
+                name: name.clone(),
+                dimension: engine.dimension() as u32,
+                state: Some(engine.to_state()),
+            })
+            .collect();
+        collections.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let state = DatabaseState {
+            format_version: 1,
+            collections,
+        };
+        let mut buf: Vec<u8> = Vec::new();
+        state.encode(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// `save_to_bytes`로 저장한 스냅샷에서 모든 컬렉션을 복원합니다.
+    pub fn load_from_bytes(bytes: &[u8]) -> Result<Self, VectorEngineError> {
+        let state = DatabaseState::decode(bytes)?;
+        let mut database = Self::new();
+        for collection in state.collections {
+            if database.collections.contains_key(&collection.name) {
+                return Err(VectorEngineError::DeserializationError(format!(
+                    "스냅샷에 컬렉션 '{}'이 중복되어 있습니다.",
+                    collection.name
+                )));
+            }
+            let engine = VectorEngine::from_state(collection.state.unwrap_or_default(), collection.dimension as usize)?;
+            database.collections.insert(collection.name, engine);
+        }
+        Ok(database)
+    }
+}
@@ -1,11 +1,67 @@
 include!(concat!(env!("OUT_DIR"), "/engine.rs"));
 
+use crate::models::{point::Metric, search_cache::CacheWeight};
+
 // prost에서 생성한 Document 구조체에 impl 선언
 impl Document {
     pub fn new(id: u64, vector: Vec<f32>) -> Self {
         Self {
             id,
             vector,
+            ..Default::default()
+        }
+    }
+}
+impl SparseVector {
+    /// (인덱스, 값) 목록으로 희소 벡터를 생성합니다.
+    ///
+    /// 인덱스는 오름차순으로 정렬되고, 중복된 인덱스의 값은 더해지며, 0인 값은 제거됩니다.
+    /// 두 목록의 길이가 다르면 None을 반환합니다.
+    pub fn new(indices: Vec<u32>, values: Vec<f32>) -> Option<Self> {
+        if indices.len() != values.len() {
+            return None;
+        }
+        let mut pairs: Vec<(u32, f32)> = indices.into_iter().zip(values).collect();
+        pairs.sort_by_key(|pair| pair.0);
+
+        let mut merged: Vec<(u32, f32)> = Vec::with_capacity(pairs.len());
+        for (index, value) in pairs {
+            match merged.last_mut() {
+                Some(last) if last.0 == index => last.1 += value,
+                _ => merged.push((index, value)),
+            }
+        }
+        merged.retain(|pair| pair.1 != 0.0);
+
+        Some(Self {
+            indices: merged.iter().map(|pair| pair.0).collect(),
+            values: merged.iter().map(|pair| pair.1).collect(),
+        })
+    }
+}
+
+impl CacheWeight for CachedEmbedding {
+    fn weight(&self) -> usize {
+        std::mem::size_of::<Self>() + self.vector.capacity() * std::mem::size_of::<f32>() + self.text.capacity()
+    }
+}
+
+impl From<Metric> for DistanceMetric {
+    fn from(metric: Metric) -> Self {
+        match metric {
+            Metric::Cosine => DistanceMetric::Cosine,
+            Metric::Euclidean => DistanceMetric::Euclidean,
+            Metric::DotProduct => DistanceMetric::DotProduct,
+        }
+    }
+}
+
+impl From<DistanceMetric> for Metric {
+    fn from(metric: DistanceMetric) -> Self {
+        match metric {
+            DistanceMetric::Cosine => Metric::Cosine,
+            DistanceMetric::Euclidean => Metric::Euclidean,
+            DistanceMetric::DotProduct => Metric::DotProduct,
         }
     }
-}
\ No newline at end of file
+}
@@ -1,37 +1,352 @@
 use crate::{
     models::errors::VectorEngineError,
-    models::point::MyPoint,
+    models::point::{Metric, MetricPoint},
     models::{
-        CacheStats, SearchCache,
-        document::{Document, EngineState},
+        CacheConfig, CacheStats, CachedQuery, CachedSearch, SearchCache,
+        document::{
+            CachedEmbedding, DeltaSnapshot, DistanceMetric, Document, EngineState, NamedVector, PayloadUpdate,
+            SparseVector, TokenDocument, TokenVector, VectorSpaceConfig, WalRecord, wal_record,
+        },
+        filter::Filter,
+        fusion::{FusionMethod, fuse, normalize_min_max, weighted_fusion},
+        id_map::IdMap,
+        late_interaction::LateInteractionStore,
+        path_index::{PathIndex, PathScope},
+        payload::{Payload, merge_patch},
+        record::{DocumentRecord, GroupHit, IngestReport, ScrollPage},
+        semantic_cache::{SemanticCache, SemanticCacheStats},
+        sparse_index::SparseIndex,
+        vector_space::VectorSpace,
+        text_index::TextIndex,
+        wal::{WalReplay, WriteAheadLog, read_frames},
     },
-    utils::hash_vector,
+    utils::hash_text,
 };
-use instant_distance::{Builder, HnswMap, Search};
+use instant_distance::{Builder, HnswMap, Point, Search};
 use prost::Message;
-use rand::seq::index;
-use serde::de::value;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::Bound;
+use std::io::Write;
+
+/// 후보를 모은 뒤 재정렬하는 검색(하이브리드, 추천 등)에서 top_k의 몇 배수만큼 후보를 가져올지
+const CANDIDATE_POOL_FACTOR: usize = 4;
+
+/// 임베딩 캐시의 기본 최대 항목 수
+const DEFAULT_EMBEDDING_CACHE_ENTRIES: usize = 10_000;
+
+/// HNSW 검색 시 유지하는 후보 수의 기본값 (instant-distance 기본값과 동일)
+const DEFAULT_EF_SEARCH: usize = 100;
+
+/// upsert 호출 결과, 새 문서가 추가되었는지 기존 문서가 갱신되었는지를 나타냄
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum UpsertOutcome {
+    Inserted,
+    Updated,
+}
+
+/// recommend에서 긍정/부정 예시를 점수에 반영하는 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecommendStrategy {
+    /// Rocchio 방식: `평균(긍정) + (평균(긍정) - 평균(부정))` 벡터 하나로 검색
+    AverageVector,
+    /// 후보마다 모든 예시와 비교해 가장 가까운 긍정/부정 예시로 점수를 매김
+    BestScore,
+}
+
+/// 그룹 검색에서 그룹 점수를 계산하는 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupAggregate {
+    /// 그룹 내 가장 가까운 청크의 유사도
+    Max,
+    /// 반환되는 상위 청크들의 평균 유사도
+    Mean,
+}
 
 pub struct VectorEngine {
-    index: HnswMap<MyPoint, u64>,
-    query_cache: SearchCache<'static, u64, Vec<(u64, f32)>>,
+    index: HnswMap<MetricPoint, u64>,
+    metric: Metric,
+    ef_search: usize,
+    query_cache: SearchCache<'static, u64, CachedSearch>,
+    semantic_cache: Option<SemanticCache>,
+    /// 텍스트 내용 해시 -> 임베딩 (같은 텍스트를 다시 임베딩하지 않기 위함, 스냅샷에 저장됨)
+    embedding_cache: SearchCache<'static, u64, CachedEmbedding>,
+    /// 변경 사항을 덧붙이는 쓰기 전 로그 (연결된 경우에만 기록)
+    wal: Option<WriteAheadLog>,
+    /// 마지막으로 기록하거나 재생한 WAL 레코드 번호
+    wal_sequence: u64,
+    /// 마지막 스냅샷 세대 번호 (델타 스냅샷을 저장할 때마다 증가)
+    generation: u64,
+    /// 마지막 스냅샷 이후 추가·변경·삭제된 문서 ID (델타 스냅샷에 담김)
+    dirty_documents: HashSet<u64>,
+    dirty_token_documents: HashSet<u64>,
+    /// 델타로 만들었지만 아직 `commit_delta`로 확정되지 않은 문서 ID
+    pending_documents: HashSet<u64>,
+    pending_token_documents: HashSet<u64>,
     dimension: usize,
     documents: HashMap<u64, Vec<f32>>,
+    /// 정렬된 문서 ID (scroll이 커서 위치부터 바로 순회하기 위함)
+    document_ids: BTreeSet<u64>,
+    paths: HashMap<u64, String>,
+    path_index: PathIndex,
+    keys: IdMap,
+    next_id: u64,
+    payloads: HashMap<u64, Payload>,
+    texts: HashMap<u64, String>,
+    text_index: TextIndex,
+    groups: HashMap<u64, String>,
+    group_members: HashMap<String, HashSet<u64>>,
+    token_documents: LateInteractionStore,
+    sparse_index: SparseIndex,
+    vector_spaces: HashMap<String, VectorSpace>,
+}
+
+/// WAL 기록에 실패했을 때 되돌릴 변경 전 상태
+struct UndoPoint {
+    next_id: u64,
+    /// 변경 전에 존재하던 문서들 (여기 없는 ID는 새로 추가된 문서이므로 제거)
+    documents: HashMap<u64, Document>,
 }
 
 impl VectorEngine {
     /// 지정된  차원의 비어 있는 새로운 VectorEngine을 생성
     pub fn new(dimension: usize) -> Self {
-        let points: Vec<MyPoint> = Vec::new();
+        Self::with_metric(dimension, Metric::Cosine)
+    }
+
+    /// 지정된 차원과 거리 계산 방식으로 비어 있는 새로운 VectorEngine을 생성
+    pub fn with_metric(dimension: usize, metric: Metric) -> Self {
+        let points: Vec<MetricPoint> = Vec::new();
         let values: Vec<u64> = Vec::new();
         let index = Builder::default().build(points, values);
         VectorEngine {
             dimension,
-            index: index,
-            query_cache: SearchCache::new(100),
+            index,
+            metric,
+            ef_search: DEFAULT_EF_SEARCH,
+            query_cache: SearchCache::with_config(CacheConfig::default()),
+            semantic_cache: None,
+            embedding_cache: SearchCache::with_config(CacheConfig {
+                max_entries: DEFAULT_EMBEDDING_CACHE_ENTRIES,
+                ..CacheConfig::default()
+            }),
+            wal: None,
+            wal_sequence: 0,
+            generation: 0,
+            dirty_documents: HashSet::new(),
+            dirty_token_documents: HashSet::new(),
+            pending_documents: HashSet::new(),
+            pending_token_documents: HashSet::new(),
             documents: HashMap::new(),
+            document_ids: BTreeSet::new(),
+            paths: HashMap::new(),
+            path_index: PathIndex::new(),
+            keys: IdMap::new(),
+            next_id: 0,
+            payloads: HashMap::new(),
+            texts: HashMap::new(),
+            text_index: TextIndex::new(),
+            groups: HashMap::new(),
+            group_members: HashMap::new(),
+            token_documents: LateInteractionStore::new(metric),
+            sparse_index: SparseIndex::new(),
+            vector_spaces: HashMap::new(),
+        }
+    }
+
+    /// 문서 벡터를 저장하고 정렬된 ID 목록에 추가 (직접 지정된 ID는 할당기를 움직이지 않음)
+    fn store_vector(&mut self, id: u64, vector: Vec<f32>) {
+        self.documents.insert(id, vector);
+        self.document_ids.insert(id);
+    }
+
+    /// 사용 중이지 않은 다음 내부 ID를 할당 (단조 증가, 할당했던 ID는 삭제 후에도 재사용하지 않음)
+    ///
+    /// 직접 지정된 ID는 건너뛰기만 하므로, 해시 같은 큰 ID가 있어도 자동 ID는 작은 값에서 이어집니다.
+    fn allocate_id(&mut self) -> Result<u64, VectorEngineError> {
+        let exhausted = || VectorEngineError::InvalidInput("더 이상 할당할 수 있는 ID가 없습니다.".to_string());
+        while self.has_document(&self.next_id) {
+            self.next_id = self.next_id.checked_add(1).ok_or_else(exhausted)?;
+        }
+        let id = self.next_id;
+        self.next_id = id.checked_add(1).ok_or_else(exhausted)?;
+        Ok(id)
+    }
+
+    /// 문자열 ID에 해당하는 내부 ID를 찾고, 없으면 ItemNotFound를 반환
+    fn resolve_key(&self, key: &str) -> Result<u64, VectorEngineError> {
+        self.keys.id_of(key).ok_or_else(|| {
+            VectorEngineError::ItemNotFound(format!("문자열 ID '{}'에 맞는 문서가 존재하지 않습니다.", key))
+        })
+    }
+
+    /// 메인 벡터나 이름 있는 벡터 중 하나라도 가진 문서인지 확인
+    ///
+    /// 다른 차원의 모델로 옮기는 중에는 이름 있는 벡터만 가진 문서가 있을 수 있습니다.
+    fn has_document(&self, id: &u64) -> bool {
+        self.documents.contains_key(id) || self.vector_spaces.values().any(|space| space.get(id).is_some())
+    }
+
+    /// 문서의 현재 상태를 저장용 Document 메시지로 만듭니다. 메인 벡터가 없으면 빈 벡터로 담깁니다.
+    fn document_state(&self, id: u64) -> Option<Document> {
+        if !self.has_document(&id) {
+            return None;
+        }
+        Some(self.to_document(id, self.documents.get(&id).map_or(&[], Vec::as_slice)))
+    }
+
+    /// 저장용 Document 메시지로 변환
+    fn to_document(&self, id: u64, vector: &[f32]) -> Document {
+        Document {
+            id,
+            vector: vector.to_vec(),
+            path: self.paths.get(&id).cloned().unwrap_or_default(),
+            external_id: self.keys.key_of(id).unwrap_or_default().to_string(),
+            payload: self
+                .payloads
+                .get(&id)
+                .map(|payload| Value::Object(payload.clone()).to_string())
+                .unwrap_or_default(),
+            text: self.texts.get(&id).cloned().unwrap_or_default(),
+            group: self.groups.get(&id).cloned().unwrap_or_default(),
+            sparse: self.sparse_index.get(&id).cloned(),
+            named_vectors: self
+                .vector_spaces
+                .iter()
+                .filter_map(|(name, space)| {
+                    space.get(&id).map(|vector| NamedVector { name: name.clone(), vector: vector.clone() })
+                })
+                .collect(),
+        }
+    }
+
+    /// Document 메시지의 내용을 엔진 상태에 반영 (인덱스 재구성은 호출자가 담당)
+    fn restore_document(&mut self, doc: Document) -> Result<(), VectorEngineError> {
+        if doc.payload.is_empty() {
+            self.payloads.remove(&doc.id);
+        } else {
+            let payload: Payload = serde_json::from_str(&doc.payload)
+                .map_err(|e| VectorEngineError::DeserializationError(e.to_string()))?;
+            self.payloads.insert(doc.id, payload);
+        }
+        self.assign_path(doc.id, &doc.path);
+        self.assign_text(doc.id, &doc.text);
+        self.assign_group(doc.id, &doc.group);
+        match doc.sparse {
+            Some(sparse) => self.sparse_index.insert(doc.id, sparse),
+            None => {
+                self.sparse_index.remove(&doc.id);
+            }
+        }
+        for space in self.vector_spaces.values_mut() {
+            space.remove(&doc.id);
+        }
+        for named in doc.named_vectors {
+            let space = self.vector_spaces.get_mut(&named.name).ok_or_else(|| {
+                VectorEngineError::DeserializationError(format!(
+                    "문서 {}가 존재하지 않는 벡터 공간 '{}'을 참조합니다.",
+                    doc.id, named.name
+                ))
+            })?;
+            space.load(doc.id, named.vector)?;
+        }
+        if doc.external_id.is_empty() {
+            self.keys.remove_by_id(doc.id);
+        } else {
+            self.keys.insert(&doc.external_id, doc.id);
         }
+        // 빈 벡터는 이름 있는 벡터만 가진 문서
+        if doc.vector.is_empty() {
+            self.documents.remove(&doc.id);
+            self.document_ids.remove(&doc.id);
+        } else {
+            self.store_vector(doc.id, doc.vector);
+        }
+        Ok(())
+    }
+
+    /// 문서가 없으면 ItemNotFound를 반환
+    fn ensure_exists(&self, id: &u64) -> Result<(), VectorEngineError> {
+        if !self.has_document(id) {
+            return Err(VectorEngineError::ItemNotFound(format!(
+                "입력한 id {}에 맞는 문서가 존재하지 않습니다.",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    /// 엔진의 거리 계산 방식을 적용한 HNSW 포인트를 생성
+    fn point(&self, vector: &[f32]) -> MetricPoint {
+        MetricPoint { vector: vector.to_vec(), metric: self.metric }
+    }
+
+    /// 입력 벡터의 차원이 엔진의 차원과 일치하는지 검사
+    fn ensure_dimension(&self, vector: &[f32]) -> Result<(), VectorEngineError> {
+        if self.dimension != vector.len() {
+            let error_message = format!(
+                "입력 벡터의 차원({})이 엔진의 차원({})과 일치하지 않습니다.",
+                vector.len(),
+                self.dimension
+            );
+            return Err(VectorEngineError::DimensionMismatch(error_message));
+        }
+        Ok(())
+    }
+
+    /// HNSW 인덱스를 탐색하며 `accept`를 통과한 결과를 최대 top_k개까지 반환
+    fn index_search(
+        &self,
+        query_point: &MetricPoint,
+        top_k: usize,
+        accept: impl Fn(u64) -> bool,
+    ) -> Vec<(u64, f32)> {
+        let mut search = Search::default();
+        self.index
+            .search(query_point, &mut search)
+            .filter(|item| accept(*item.value))
+            .take(top_k)
+            .map(|item| (*item.value, item.distance))
+            .collect()
+    }
+
+    /// HNSW 결과를 `accept`로 걸러내고, 걸러진 탓에 top_k를 채우지 못하면 전수 비교로 보완
+    fn filtered_search(
+        &self,
+        query_point: &MetricPoint,
+        top_k: usize,
+        accept: impl Fn(u64) -> bool,
+    ) -> Vec<(u64, f32)> {
+        let results = self.index_search(query_point, top_k, &accept);
+        if results.len() >= top_k {
+            return results;
+        }
+        let eligible: Vec<u64> = self.documents.keys().copied().filter(|id| accept(*id)).collect();
+        if results.len() >= eligible.len() {
+            return results;
+        }
+        self.exact_search(query_point, eligible, top_k)
+    }
+
+    /// 주어진 ID 집합에 대해 전수 거리 계산을 수행 (거리 오름차순, 최대 top_k개)
+    fn exact_search(
+        &self,
+        query_point: &MetricPoint,
+        ids: impl IntoIterator<Item = u64>,
+        top_k: usize,
+    ) -> Vec<(u64, f32)> {
+        let mut results: Vec<(u64, f32)> = ids
+            .into_iter()
+            .filter_map(|id| {
+                self.documents
+                    .get(&id)
+                    .map(|v| (id, self.metric.distance(&query_point.vector, v)))
+            })
+            .collect();
+        results.sort_by(|a, b| a.1.total_cmp(&b.1));
+        results.truncate(top_k);
+        results
     }
 
     /// 현재 documents의 내용을 바탕으로 HNSW 인덱스를 재생성
@@ -40,21 +355,368 @@ impl VectorEngine {
         let points = self
             .documents
             .values()
-            .map(|v| MyPoint(v.clone()))
+            .map(|v| self.point(v))
             .collect();
         let values: Vec<u64> = self.documents.keys().copied().collect();
         // 4. 기존 인덱스를 교체
-        self.index = Builder::default().build(points, values);
+        self.index = Builder::default().ef_search(self.ef_search).build(points, values);
+
+        // 5. 삭제가 반영되지 않은 이름 있는 벡터 공간도 재구성
+        for space in self.vector_spaces.values_mut() {
+            space.rebuild_if_dirty();
+        }
 
         Ok(())
     }
 
-    /// 테스트 목적으로 캐시에 저장된 항목의 수를 반환합니다.
+    /// 쓰기로 추가·변경·삭제된 문서의 영향을 받는 캐시 항목만 제거합니다.
+    ///
+    /// 해당 문서를 결과에 포함했거나, 문서가 항목의 필터를 만족하면서 현재 벡터가 결과의
+    /// 가장 먼 거리 안쪽으로 들어와 순위를 바꿀 수 있는 항목이 제거 대상입니다.
+    fn invalidate_cached_searches(&mut self, ids: &[u64]) {
+        let (documents, payloads, paths) = (&self.documents, &self.payloads, &self.paths);
+        let metric = self.metric;
+        let is_stale = |entry: &CachedSearch| {
+            ids.iter().any(|id| {
+                if entry.contains_id(*id) {
+                    return true;
+                }
+                let Some(vector) = documents.get(id) else {
+                    return false;
+                };
+                let eligible = entry.query.filter.as_ref().is_none_or(|f| {
+                    f.matches(payloads.get(id), paths.get(id).map(String::as_str))
+                });
+                eligible && entry.admits(metric.distance(&entry.query.vector, vector))
+            })
+        };
+        self.query_cache.retain(|_, entry| !is_stale(entry));
+        if let Some(semantic) = self.semantic_cache.as_mut() {
+            semantic.retain(|entry| !is_stale(entry));
+        }
+    }
+
+    /// WAL이 연결되어 있으면 변경 전 문서들의 상태를 담은 되돌림 지점을 만듭니다.
+    ///
+    /// 변경 전에 호출해야 하며, 기록에 실패했을 때 `record_changes`가 이 지점으로 되돌립니다.
+    fn undo_point(&self, ids: &[u64]) -> Option<UndoPoint> {
+        self.wal.as_ref()?;
+        let documents = ids
+            .iter()
+            .filter_map(|id| self.document_state(*id).map(|doc| (*id, doc)))
+            .collect();
+        Some(UndoPoint { next_id: self.next_id, documents })
+    }
+
+    /// 문서가 추가·변경·삭제된 뒤 호출하여 WAL에 변경 후 상태를 기록하고, 쿼리 캐시를
+    /// 무효화하며, 델타 대상으로 표시합니다.
+    ///
+    /// 남아 있는 문서는 전체 상태(put)로, 사라진 문서는 delete로 한 번에 기록됩니다.
+    /// 기록에 실패하면 `undo` 지점으로 되돌린 뒤 오류를 반환하므로, 로그에 없는 변경이 남지 않습니다.
+    fn record_changes(&mut self, ids: &[u64], undo: Option<UndoPoint>) -> Result<(), VectorEngineError> {
+        self.record_space_changes(ids, undo)?;
+        self.invalidate_cached_searches(ids);
+        Ok(())
+    }
+
+    /// 이름 있는 벡터만 바뀐 문서들을 기록합니다. 메인 공간의 쿼리 캐시는 영향을 받지 않으므로 그대로 둡니다.
+    fn record_space_changes(&mut self, ids: &[u64], undo: Option<UndoPoint>) -> Result<(), VectorEngineError> {
+        if self.wal.is_some() {
+            let ops = ids
+                .iter()
+                .map(|&id| match self.document_state(id) {
+                    Some(doc) => wal_record::Op::Put(doc),
+                    None => wal_record::Op::Delete(id),
+                })
+                .collect();
+            self.append_or_roll_back(ids, ops, undo)?;
+        }
+        self.dirty_documents.extend(ids);
+        Ok(())
+    }
+
+    /// 메타데이터만 바뀐 문서들에 대해 `record_changes`와 같은 처리를 하되, WAL에는 메타데이터만 기록합니다.
+    fn record_payload_changes(&mut self, ids: &[u64], undo: Option<UndoPoint>) -> Result<(), VectorEngineError> {
+        if self.wal.is_some() {
+            let ops = ids
+                .iter()
+                .map(|&id| {
+                    let payload = self
+                        .payloads
+                        .get(&id)
+                        .map(|payload| Value::Object(payload.clone()).to_string())
+                        .unwrap_or_default();
+                    wal_record::Op::Payload(PayloadUpdate { id, payload })
+                })
+                .collect();
+            self.append_or_roll_back(ids, ops, undo)?;
+        }
+        self.invalidate_cached_searches(ids);
+        self.dirty_documents.extend(ids);
+        Ok(())
+    }
+
+    /// 레코드들을 기록하고, 실패하면 `ids` 문서들을 `undo` 지점의 상태로 되돌립니다.
+    fn append_or_roll_back(
+        &mut self,
+        ids: &[u64],
+        ops: Vec<wal_record::Op>,
+        undo: Option<UndoPoint>,
+    ) -> Result<(), VectorEngineError> {
+        let Err(error) = self.append_wal(ops) else {
+            return Ok(());
+        };
+        if let Some(UndoPoint { next_id, documents }) = undo {
+            for &id in ids {
+                if !documents.contains_key(&id) {
+                    self.remove_document_state(id);
+                }
+            }
+            for doc in documents.into_values() {
+                self.restore_document(doc)?;
+            }
+            self.next_id = next_id;
+            self.rebuild_index()?;
+        }
+        Err(error)
+    }
+
+    /// WAL이 연결되어 있으면 이어지는 번호로 레코드들을 한 번에 기록합니다.
+    fn append_wal(&mut self, ops: Vec<wal_record::Op>) -> Result<(), VectorEngineError> {
+        let Some(wal) = self.wal.as_mut() else {
+            return Ok(());
+        };
+        let records: Vec<WalRecord> = ops
+            .into_iter()
+            .zip(self.wal_sequence + 1..)
+            .map(|(op, sequence)| WalRecord { sequence, next_id: self.next_id, op: Some(op) })
+            .collect();
+        wal.append_all(&records)?;
+        self.wal_sequence += records.len() as u64;
+        Ok(())
+    }
+
+    /// 이후의 모든 변경을 `sink`에 WAL 레코드로 기록합니다. 이미 연결된 로그가 있으면 교체합니다.
+    ///
+    /// 교체되는 로그는 먼저 flush하며, flush에 실패하면 기존 로그를 그대로 두고 오류를 반환합니다.
+    /// (이미 쓰기에 실패한 로그는 flush 없이 교체됩니다.) 기존 sink를 돌려받으려면 `detach_wal`을 쓰세요.
+    /// 변경은 레코드가 기록되고 flush된 뒤에야 확정되며, 기록에 실패한 변경은 되돌려집니다.
+    /// 파일의 fsync 등은 sink가 담당합니다.
+    pub fn attach_wal<W: Write + Send + 'static>(&mut self, sink: W) -> Result<(), VectorEngineError> {
+        if let Some(wal) = self.wal.as_mut()
+            && !wal.has_failed()
+        {
+            wal.flush()?;
+        }
+        self.wal = Some(WriteAheadLog::new(Box::new(sink)));
+        Ok(())
+    }
+
+    /// WAL 기록을 멈추고 연결되어 있던 sink를 돌려줍니다.
+    pub fn detach_wal(&mut self) -> Option<Box<dyn Write + Send>> {
+        self.wal.take().map(WriteAheadLog::into_inner)
+    }
+
+    /// 마지막으로 기록하거나 재생한 WAL 레코드 번호를 반환합니다.
+    pub fn wal_sequence(&self) -> u64 {
+        self.wal_sequence
+    }
+
+    /// 현재 상태를 새 스냅샷으로 만들고, 이후 기록은 `new_sink`로 보냅니다.
+    ///
+    /// 반환된 스냅샷에는 지금까지의 WAL 번호가 함께 저장되므로, 호출자는 스냅샷을 안전하게
+    /// 저장한 뒤 이전 로그를 버리면 됩니다. 그 전에 중단되더라도 이전 로그의 레코드는 재생 시
+    /// 번호로 걸러집니다. `compact`처럼 델타용 변경 추적도 초기화합니다.
+    pub fn checkpoint<W: Write + Send + 'static>(&mut self, new_sink: W) -> Result<Vec<u8>, VectorEngineError> {
+        if let Some(wal) = self.wal.as_mut() {
+            wal.flush()?;
+        }
+        let snapshot = self.save_to_bytes()?;
+        self.attach_wal(new_sink)?;
+        Ok(snapshot)
+    }
+
+    /// 스냅샷 이후의 WAL을 재생합니다. 스냅샷에 이미 반영된 번호의 레코드는 건너뜁니다.
+    ///
+    /// 잘리거나 체크섬이 맞지 않는 꼬리는 무시되며, 잘라내야 할 위치는 결과의 `valid_len`입니다.
+    /// 재생 중인 변경은 연결된 WAL에 다시 기록되지 않습니다. 레코드를 반영하다 실패하면
+    /// 그때까지 반영한 상태로 인덱스를 재구성한 뒤 오류를 반환합니다.
+    pub fn replay_wal(&mut self, log: &[u8]) -> Result<WalReplay, VectorEngineError> {
+        let (records, valid_len) = read_frames(log);
+        let mut replay = WalReplay {
+            valid_len,
+            truncated_bytes: log.len() - valid_len,
+            ..WalReplay::default()
+        };
+        let mut outcome = Ok(());
+        for record in records {
+            if record.sequence <= self.wal_sequence {
+                replay.skipped += 1;
+                continue;
+            }
+            if let Some(op) = record.op
+                && let Err(error) = self.apply_wal_op(op)
+            {
+                outcome = Err(error);
+                break;
+            }
+            self.next_id = self.next_id.max(record.next_id);
+            self.wal_sequence = record.sequence;
+            replay.applied += 1;
+        }
+        // 중간에 실패해도 이미 반영된 레코드가 인덱스와 캐시에 어긋나지 않도록 재구성
+        if replay.applied > 0 || outcome.is_err() {
+            self.reset_after_restore()?;
+        }
+        outcome.map(|_| replay)
+    }
+
+    /// 저장된 상태를 직접 반영한 뒤 인덱스를 재구성하고 쿼리 캐시를 비웁니다.
+    fn reset_after_restore(&mut self) -> Result<(), VectorEngineError> {
+        self.rebuild_index()?;
+        self.token_documents.rebuild_index(self.ef_search);
+        self.query_cache.clear();
+        if let Some(semantic) = self.semantic_cache.as_mut() {
+            semantic.clear();
+        }
+        Ok(())
+    }
+
+    /// WAL 레코드 하나를 엔진 상태에 반영 (인덱스 재구성은 호출자가 담당)
+    ///
+    /// 재생된 변경은 마지막 스냅샷에 없으므로 델타 대상으로 표시됩니다.
+    fn apply_wal_op(&mut self, op: wal_record::Op) -> Result<(), VectorEngineError> {
+        match op {
+            wal_record::Op::Put(doc) => {
+                self.dirty_documents.insert(doc.id);
+                self.restore_document(doc)?;
+            }
+            wal_record::Op::Delete(id) => {
+                self.dirty_documents.insert(id);
+                self.remove_document_state(id);
+            }
+            wal_record::Op::Payload(update) => {
+                // 문서 없이 메타데이터만 남지 않도록, 재생 시점에 없는 문서의 기록은 건너뜀
+                if !self.has_document(&update.id) {
+                    return Ok(());
+                }
+                self.dirty_documents.insert(update.id);
+                if update.payload.is_empty() {
+                    self.payloads.remove(&update.id);
+                } else {
+                    let payload: Payload = serde_json::from_str(&update.payload)
+                        .map_err(|e| VectorEngineError::DeserializationError(e.to_string()))?;
+                    self.payloads.insert(update.id, payload);
+                }
+            }
+            wal_record::Op::CreateSpace(config) => {
+                let metric = Metric::from(config.metric());
+                self.vector_spaces
+                    .entry(config.name.clone())
+                    .or_insert_with(|| VectorSpace::new(&config.name, config.dimension as usize, metric));
+            }
+            wal_record::Op::DropSpace(name) => {
+                self.vector_spaces.remove(&name);
+            }
+            wal_record::Op::PutTokens(doc) => {
+                self.dirty_token_documents.insert(doc.id);
+                self.restore_token_document(doc);
+            }
+            wal_record::Op::DeleteTokens(id) => {
+                self.dirty_token_documents.insert(id);
+                self.token_documents.remove(&id);
+            }
+        }
+        Ok(())
+    }
+
+    /// 거의 같은 질의 벡터에 이전 결과를 재사용하는 의미 기반 캐시를 켭니다.
+    ///
+    /// `threshold`는 적중으로 볼 질의 벡터 간 코사인 유사도의 최솟값이며, 이미 켜져 있었다면
+    /// 기존 항목과 통계를 버리고 새로 시작합니다.
+    pub fn enable_semantic_cache(&mut self, capacity: usize, threshold: f32) {
+        self.semantic_cache = Some(SemanticCache::new(capacity, threshold));
+    }
+
+    pub fn disable_semantic_cache(&mut self) {
+        self.semantic_cache = None;
+    }
+
+    /// 의미 기반 캐시가 켜져 있으면 그 적중 통계를 반환합니다.
+    pub fn semantic_cache_stats(&self) -> Option<&SemanticCacheStats> {
+        self.semantic_cache.as_ref().map(SemanticCache::stats)
+    }
+
+    /// 쿼리 캐시의 교체 정책, 용량, TTL을 바꿉니다. 새 용량을 넘는 항목은 즉시 제거됩니다.
+    pub fn configure_query_cache(&mut self, config: CacheConfig) {
+        self.query_cache.set_config(config);
+    }
+
+    pub fn query_cache_config(&self) -> &CacheConfig {
+        self.query_cache.config()
+    }
+
+    /// 쿼리 캐시의 최대 항목 수를 바꿉니다. 넘치는 항목은 정책에 따라 제거됩니다.
+    pub fn resize_query_cache(&mut self, max_entries: usize) {
+        let config = CacheConfig { max_entries, ..self.query_cache.config().clone() };
+        self.query_cache.set_config(config);
+    }
+
+    /// 쿼리 캐시를 켜거나 끕니다. 끄면 저장된 결과를 비우고 이후 검색은 캐시를 거치지 않습니다.
+    pub fn set_query_cache_enabled(&mut self, enabled: bool) {
+        self.query_cache.set_enabled(enabled);
+    }
+
+    pub fn query_cache_enabled(&self) -> bool {
+        self.query_cache.is_enabled()
+    }
+
+    /// 쿼리 캐시의 항목만 비웁니다. 통계는 유지됩니다.
+    pub fn clear_query_cache(&mut self) {
+        self.query_cache.clear();
+    }
+
+    /// 쿼리 캐시의 누적 통계만 초기화합니다.
+    pub fn reset_query_cache_stats(&mut self) {
+        self.query_cache.reset_stats();
+    }
+
+    /// 자주 쓰일 질의들을 미리 검색해 캐시에 채워 넣고, 새로 저장된 항목 수를 반환합니다.
+    ///
+    /// 이미 답할 수 있는 항목이 있는 질의는 건너뛰며, 히트/미스 통계에는 반영되지 않습니다.
+    pub fn warm_query_cache(&mut self, queries: &[Vec<f32>], top_k: usize) -> Result<usize, VectorEngineError> {
+        for query_vector in queries {
+            self.ensure_dimension(query_vector)?;
+        }
+        if !self.query_cache.is_enabled() {
+            return Ok(0);
+        }
+        let mut warmed = 0;
+        for query_vector in queries {
+            let query = self.cached_query(query_vector, None);
+            let fingerprint = query.fingerprint();
+            let answered = self
+                .query_cache
+                .peek(&fingerprint)
+                .is_some_and(|entry| entry.query.same_as(&query) && entry.answer(top_k).is_some());
+            if answered {
+                continue;
+            }
+            let results = self.run_search(query_vector, top_k, None);
+            self.store_search(fingerprint, CachedSearch::new(query, top_k, results));
+            warmed += 1;
+        }
+        Ok(warmed)
+    }
+
+    /// 캐시에 저장된 항목의 수를 반환합니다.
     pub fn query_cache_len(&self) -> usize {
         self.query_cache.len()
     }
 
-    /// 테스트 목적으로 캐시의 히트/미스 통계를 반환합니다.
+    /// 쿼리 캐시의 통계(히트, 미스, 제거, 현재 항목 수 등)를 반환합니다.
+    ///
+    /// 쓰기에 따른 무효화나 `clear_query_cache`로는 초기화되지 않습니다.
     pub fn query_cache_stats(&self) -> &CacheStats {
         self.query_cache.stats()
     }
@@ -63,13 +725,552 @@ impl VectorEngine {
         self.documents.len()
     }
 
-    pub fn dimension(&self) -> usize {
-        self.dimension
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    /// 엔진에 저장된 documents 해시맵의 불변 참조를 반환합니다.
+    pub fn documents(&self) -> &HashMap<u64, Vec<f32>> {
+        &self.documents
+    }
+
+    /// 문서에 지정된 경로를 반환합니다.
+    pub fn document_path(&self, id: &u64) -> Option<&str> {
+        self.paths.get(id).map(String::as_str)
+    }
+
+    /// 문자열 ID에 매핑된 내부 ID를 반환합니다.
+    pub fn document_id(&self, key: &str) -> Option<u64> {
+        self.keys.id_of(key)
+    }
+
+    /// 내부 ID에 매핑된 문자열 ID를 반환합니다.
+    pub fn document_key(&self, id: &u64) -> Option<&str> {
+        self.keys.key_of(*id)
+    }
+
+    /// 문자열 ID로 문서를 추가합니다. 이미 있는 키라면 같은 내부 ID의 벡터를 덮어씁니다.
+    ///
+    /// 새 키에는 엔진이 내부 ID를 할당하며, 할당된 내부 ID를 반환합니다.
+    pub fn add_document_by_key(&mut self, key: &str, vector: Vec<f32>) -> Result<u64, VectorEngineError> {
+        self.ensure_dimension(&vector)?;
+        let existing = self.keys.id_of(key);
+        let undo = self.undo_point(existing.as_slice());
+        let id = match existing {
+            Some(id) => id,
+            None => self.allocate_id()?,
+        };
+        self.store_vector(id, vector);
+        self.keys.insert(key, id);
+        self.rebuild_index()?;
+        self.record_changes(&[id], undo)?;
+        Ok(id)
+    }
+
+    pub fn update_document_by_key(&mut self, key: &str, new_vector: Vec<f32>) -> Result<(), VectorEngineError> {
+        let id = self.resolve_key(key)?;
+        self.update_document(&id, new_vector)
+    }
+
+    pub fn delete_document_by_key(&mut self, key: &str) -> Result<(), VectorEngineError> {
+        let id = self.resolve_key(key)?;
+        self.delete_document(&id)
+    }
+
+    /// ID를 자동 할당하여 문서를 추가하고, 할당된 ID를 반환합니다.
+    ///
+    /// 할당기는 단조 증가하며 스냅샷에 함께 저장되므로, 삭제나 재로딩 이후에도 ID가 재사용되지 않습니다.
+    pub fn insert(&mut self, vector: Vec<f32>) -> Result<u64, VectorEngineError> {
+        self.ensure_dimension(&vector)?;
+        let id = self.allocate_id()?;
+        self.add_document(id, vector)?;
+        Ok(id)
+    }
+
+    /// 여러 문서를 ID 자동 할당으로 추가합니다. 인덱스 재구성은 한 번만 수행됩니다.
+    ///
+    /// 하나라도 차원이 맞지 않으면 아무 문서도 추가하지 않습니다.
+    pub fn insert_batch(&mut self, vectors: Vec<Vec<f32>>) -> Result<Vec<u64>, VectorEngineError> {
+        let undo = self.undo_point(&[]);
+        let ids = self.insert_vectors(vectors)?;
+        self.rebuild_index()?;
+        self.record_changes(&ids, undo)?;
+        Ok(ids)
+    }
+
+    /// 차원을 모두 확인한 뒤 ID를 할당하여 벡터들을 넣습니다. (인덱스 재구성과 기록은 호출자가 담당)
+    fn insert_vectors(&mut self, vectors: Vec<Vec<f32>>) -> Result<Vec<u64>, VectorEngineError> {
+        for vector in &vectors {
+            self.ensure_dimension(vector)?;
+        }
+        let ids = (0..vectors.len()).map(|_| self.allocate_id()).collect::<Result<Vec<u64>, _>>()?;
+        for (id, vector) in ids.iter().zip(vectors) {
+            self.store_vector(*id, vector);
+        }
+        Ok(ids)
+    }
+
+    /// 벡터를 다시 임베딩하지 않고 문서의 문자열 ID만 변경합니다.
+    ///
+    /// `new_key`를 이미 다른 문서가 쓰고 있다면 AlreadyExists를 반환합니다.
+    pub fn rename_document(&mut self, old_key: &str, new_key: &str) -> Result<(), VectorEngineError> {
+        self.rename_key(old_key, new_key, false)
+    }
+
+    /// `rename_document`와 같지만, 파일 이름 변경처럼 `new_key`를 쓰던 문서는 삭제하고 덮어씁니다.
+    pub fn rename_document_replacing(&mut self, old_key: &str, new_key: &str) -> Result<(), VectorEngineError> {
+        self.rename_key(old_key, new_key, true)
+    }
+
+    fn rename_key(&mut self, old_key: &str, new_key: &str, overwrite: bool) -> Result<(), VectorEngineError> {
+        let id = self.resolve_key(old_key)?;
+        if old_key == new_key {
+            return Ok(());
+        }
+        if let Some(target_id) = self.keys.id_of(new_key) {
+            if !overwrite {
+                return Err(VectorEngineError::AlreadyExists(format!(
+                    "문자열 ID '{}'를 이미 다른 문서가 사용하고 있습니다.",
+                    new_key
+                )));
+            }
+            // 덮어쓰인 문서의 삭제와 키 변경을 하나의 변경으로 기록
+            let undo = self.undo_point(&[target_id, id]);
+            self.remove_document_state(target_id);
+            self.keys.insert(new_key, id);
+            self.rebuild_index()?;
+            return self.record_changes(&[target_id, id], undo);
+        }
+        let undo = self.undo_point(&[id]);
+        self.keys.insert(new_key, id);
+        self.record_changes(&[id], undo)
+    }
+
+    /// 해당 ID의 문서가 존재하는지 확인합니다.
+    pub fn contains(&self, id: &u64) -> bool {
+        self.has_document(id)
+    }
+
+    /// 문서 하나의 벡터와 메타데이터를 복사해 반환합니다. 이름 있는 벡터만 가진 문서의 `vector`는 비어 있습니다.
+    pub fn get(&self, id: &u64) -> Option<DocumentRecord> {
+        if !self.has_document(id) {
+            return None;
+        }
+        Some(DocumentRecord {
+            id: *id,
+            vector: self.documents.get(id).cloned().unwrap_or_default(),
+            payload: self.payloads.get(id).cloned(),
+            path: self.paths.get(id).cloned(),
+            key: self.keys.key_of(*id).map(str::to_string),
+            group: self.groups.get(id).cloned(),
+        })
+    }
+
+    /// 문서가 필터 조건을 만족하는지 검사 (필터가 없으면 항상 참)
+    fn matches_filter(&self, id: u64, filter: Option<&Filter>) -> bool {
+        filter.is_none_or(|f| {
+            f.matches(self.payloads.get(&id), self.paths.get(&id).map(String::as_str))
+        })
+    }
+
+    /// ID 오름차순으로 문서를 페이지 단위로 순회합니다.
+    ///
+    /// `cursor`는 이전 페이지의 `next_cursor`이며, 그 ID보다 큰 문서부터 반환합니다.
+    /// `limit`이 0이면 빈 페이지와 함께 받은 커서를 그대로 돌려줍니다.
+    pub fn scroll(&self, cursor: Option<u64>, limit: usize, filter: Option<&Filter>) -> ScrollPage {
+        if limit == 0 {
+            return ScrollPage { documents: Vec::new(), next_cursor: cursor };
+        }
+        let start = match cursor {
+            Some(c) => Bound::Excluded(c),
+            None => Bound::Unbounded,
+        };
+        let mut matched = self
+            .document_ids
+            .range((start, Bound::Unbounded))
+            .copied()
+            .filter(|id| self.matches_filter(*id, filter));
+        let documents: Vec<DocumentRecord> = matched
+            .by_ref()
+            .take(limit)
+            .filter_map(|id| self.get(&id))
+            .collect();
+        let next_cursor = match matched.next() {
+            Some(_) => documents.last().map(|doc| doc.id),
+            None => None,
+        };
+        ScrollPage { documents, next_cursor }
+    }
+
+    /// 문서의 메타데이터를 반환합니다.
+    pub fn payload(&self, id: &u64) -> Option<&Payload> {
+        self.payloads.get(id)
+    }
+
+    /// 메타데이터를 통째로 교체합니다. 벡터와 인덱스는 건드리지 않습니다.
+    pub fn set_payload(&mut self, id: &u64, payload: Payload) -> Result<(), VectorEngineError> {
+        self.ensure_exists(id)?;
+        let undo = self.undo_point(&[*id]);
+        if payload.is_empty() {
+            self.payloads.remove(id);
+        } else {
+            self.payloads.insert(*id, payload);
+        }
+        self.record_payload_changes(&[*id], undo)
+    }
+
+    /// JSON Merge Patch 규칙으로 메타데이터 일부만 변경합니다. 벡터와 인덱스는 건드리지 않습니다.
+    pub fn patch_payload(&mut self, id: &u64, patch: &Payload) -> Result<(), VectorEngineError> {
+        self.ensure_exists(id)?;
+        let undo = self.undo_point(&[*id]);
+        let payload = self.payloads.entry(*id).or_default();
+        merge_patch(payload, patch);
+        if payload.is_empty() {
+            self.payloads.remove(id);
+        }
+        self.record_payload_changes(&[*id], undo)
+    }
+
+    /// 문서에 지정된 키워드 검색용 텍스트를 반환합니다.
+    pub fn document_text(&self, id: &u64) -> Option<&str> {
+        self.texts.get(id).map(String::as_str)
+    }
+
+    /// 키워드 검색용 텍스트와 함께 문서를 추가합니다.
+    pub fn add_document_with_text(
+        &mut self,
+        id: u64,
+        vector: Vec<f32>,
+        text: &str,
+    ) -> Result<(), VectorEngineError> {
+        self.ensure_dimension(&vector)?;
+        let undo = self.undo_point(&[id]);
+        self.store_vector(id, vector);
+        self.assign_text(id, text);
+        self.rebuild_index()?;
+        self.record_changes(&[id], undo)
+    }
+
+    /// 텍스트 내용에 대해 캐시된 임베딩을 찾습니다. 적중/미스는 임베딩 캐시 통계에 기록됩니다.
+    ///
+    /// 해시가 같아도 저장된 원문이 다르면(해시 충돌) 미스로 처리합니다.
+    pub fn cached_embedding(&mut self, text: &str) -> Option<Vec<f32>> {
+        self.embedding_cache
+            .get_if(&hash_text(text), |entry| entry.text == text)
+            .map(|entry| entry.vector.clone())
+    }
+
+    /// 외부에서 계산한 텍스트의 임베딩을 캐시에 저장합니다.
+    pub fn cache_embedding(&mut self, text: &str, vector: Vec<f32>) -> Result<(), VectorEngineError> {
+        self.ensure_dimension(&vector)?;
+        self.store_embedding(text, vector);
+        Ok(())
+    }
+
+    fn store_embedding(&mut self, text: &str, vector: Vec<f32>) {
+        let content_hash = hash_text(text);
+        self.embedding_cache
+            .put(content_hash, CachedEmbedding { content_hash, vector, text: text.to_string() });
+    }
+
+    /// 임베딩 캐시에 없는 텍스트를 중복 없이 입력 순서대로 반환합니다. 통계에는 반영되지 않습니다.
+    pub fn uncached_texts<'t>(&self, texts: &[&'t str]) -> Vec<&'t str> {
+        let mut seen = HashSet::new();
+        texts
+            .iter()
+            .copied()
+            .filter(|text| {
+                let cached = self.embedding_cache.peek(&hash_text(text)).is_some_and(|entry| entry.text == *text);
+                !cached && seen.insert(*text)
+            })
+            .collect()
+    }
+
+    /// 텍스트 문서들을 키워드 검색용 텍스트와 함께 추가합니다.
+    ///
+    /// 서로 다른 텍스트마다 임베딩 캐시를 한 번씩 조회하고, 캐시에 없는 텍스트만 입력 순서대로 모아
+    /// `embed`를 한 번 호출한 뒤 그 결과를 캐시에 저장합니다. `embed`는 받은 텍스트와 같은 순서로
+    /// 벡터를 반환해야 하며, 결과의 두 값도 서로 다른 텍스트 수로 셉니다.
+    /// 인덱스 재구성은 마지막에 한 번만 수행됩니다.
+    pub fn add_text_documents<F>(&mut self, documents: &[(u64, &str)], embed: F) -> Result<IngestReport, VectorEngineError>
+    where
+        F: FnOnce(&[&str]) -> Result<Vec<Vec<f32>>, VectorEngineError>,
+    {
+        // 같은 배치 안에서 반복된 텍스트는 캐시를 한 번만 조회함
+        let mut vectors: HashMap<&str, Vec<f32>> = HashMap::new();
+        let mut missing: Vec<&str> = Vec::new();
+        let mut seen = HashSet::new();
+        for (_, text) in documents {
+            if !seen.insert(*text) {
+                continue;
+            }
+            match self.cached_embedding(text) {
+                Some(vector) => {
+                    vectors.insert(text, vector);
+                }
+                None => missing.push(text),
+            }
+        }
+        let cache_hits = vectors.len();
+
+        if !missing.is_empty() {
+            let embedded = embed(&missing)?;
+            if embedded.len() != missing.len() {
+                return Err(VectorEngineError::InvalidInput(format!(
+                    "임베딩 수({})가 요청한 텍스트 수({})와 일치하지 않습니다.",
+                    embedded.len(),
+                    missing.len()
+                )));
+            }
+            for vector in &embedded {
+                self.ensure_dimension(vector)?;
+            }
+            for (text, vector) in missing.iter().copied().zip(embedded) {
+                self.store_embedding(text, vector.clone());
+                vectors.insert(text, vector);
+            }
+        }
+
+        let ids: Vec<u64> = documents.iter().map(|(id, _)| *id).collect();
+        let undo = self.undo_point(&ids);
+        for (id, text) in documents {
+            self.store_vector(*id, vectors[text].clone());
+            self.assign_text(*id, text);
+        }
+        self.rebuild_index()?;
+        self.record_changes(&ids, undo)?;
+        Ok(IngestReport { cache_hits, embedded: missing.len() })
+    }
+
+    /// 임베딩 캐시의 통계(적중, 미스, 현재 항목 수 등)를 반환합니다.
+    pub fn embedding_cache_stats(&self) -> &CacheStats {
+        self.embedding_cache.stats()
+    }
+
+    /// 임베딩 캐시의 교체 정책, 용량, TTL을 바꿉니다.
+    pub fn configure_embedding_cache(&mut self, config: CacheConfig) {
+        self.embedding_cache.set_config(config);
+    }
+
+    pub fn clear_embedding_cache(&mut self) {
+        self.embedding_cache.clear();
+    }
+
+    /// 기존 문서의 키워드 검색용 텍스트를 변경합니다. 빈 문자열을 넘기면 색인에서 제거합니다.
+    pub fn set_document_text(&mut self, id: &u64, text: &str) -> Result<(), VectorEngineError> {
+        self.ensure_exists(id)?;
+        let undo = self.undo_point(&[*id]);
+        self.assign_text(*id, text);
+        self.record_changes(&[*id], undo)
+    }
+
+    /// 원문 맵과 BM25 역색인을 함께 갱신
+    fn assign_text(&mut self, id: u64, text: &str) {
+        if text.is_empty() {
+            self.texts.remove(&id);
+            self.text_index.remove(id);
+        } else {
+            self.text_index.insert(id, text);
+            self.texts.insert(id, text.to_string());
+        }
+    }
+
+    /// 이름 있는 벡터 공간을 만듭니다. 공간마다 차원과 거리 계산 방식을 따로 지정할 수 있습니다.
+    pub fn create_vector_space(&mut self, name: &str, dimension: usize, metric: Metric) -> Result<(), VectorEngineError> {
+        if self.vector_spaces.contains_key(name) {
+            return Err(VectorEngineError::AlreadyExists(format!(
+                "벡터 공간 '{}'이 이미 존재합니다.",
+                name
+            )));
+        }
+        self.append_wal(vec![wal_record::Op::CreateSpace(VectorSpaceConfig {
+            name: name.to_string(),
+            dimension: dimension as u32,
+            metric: DistanceMetric::from(metric) as i32,
+        })])?;
+        self.vector_spaces.insert(name.to_string(), VectorSpace::new(name, dimension, metric));
+        Ok(())
+    }
+
+    /// 벡터 공간과 그 안의 모든 벡터를 삭제합니다. 다른 벡터가 남는 문서는 유지되며,
+    /// 이 공간의 벡터만 가지고 있던 문서는 메타데이터와 함께 삭제됩니다.
+    pub fn drop_vector_space(&mut self, name: &str) -> Result<(), VectorEngineError> {
+        let space = self.vector_spaces.get(name).ok_or_else(|| Self::space_not_found(name))?;
+        let members: Vec<u64> = space.ids().copied().collect();
+        let orphans: Vec<u64> = space
+            .ids()
+            .copied()
+            .filter(|id| {
+                !self.documents.contains_key(id)
+                    && !self
+                        .vector_spaces
+                        .iter()
+                        .any(|(other, space)| other != name && space.get(id).is_some())
+            })
+            .collect();
+        let mut ops: Vec<wal_record::Op> = orphans.iter().map(|id| wal_record::Op::Delete(*id)).collect();
+        ops.push(wal_record::Op::DropSpace(name.to_string()));
+        self.append_wal(ops)?;
+        for id in &orphans {
+            self.remove_document_state(*id);
+        }
+        self.vector_spaces.remove(name);
+        // 이 공간의 벡터를 잃은 문서도 델타에 다시 담아, 같은 이름으로 다시 만든 공간에 옛 벡터가 남지 않게 함
+        self.dirty_documents.extend(&members);
+        Ok(())
+    }
+
+    /// 벡터 공간 이름 목록을 정렬하여 반환합니다.
+    pub fn vector_space_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.vector_spaces.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn vector_space(&self, name: &str) -> Option<&VectorSpace> {
+        self.vector_spaces.get(name)
+    }
+
+    fn space_not_found(name: &str) -> VectorEngineError {
+        VectorEngineError::ItemNotFound(format!("벡터 공간 '{}'이 존재하지 않습니다.", name))
+    }
+
+    /// 문서에 이름 있는 벡터 공간의 벡터를 지정합니다. 차원은 해당 공간 기준으로 검사합니다.
+    ///
+    /// 메인 벡터가 없는 ID라면 이름 있는 벡터만 가진 문서가 되므로, 차원이 다른 새 모델로
+    /// 옮기는 중에도 새 공간에만 문서를 넣을 수 있습니다.
+    pub fn set_named_vector(&mut self, id: &u64, space: &str, vector: Vec<f32>) -> Result<(), VectorEngineError> {
+        let undo = self.undo_point(&[*id]);
+        let target = self.vector_spaces.get_mut(space).ok_or_else(|| Self::space_not_found(space))?;
+        target.insert(*id, vector)?;
+        self.record_space_changes(&[*id], undo)
+    }
+
+    /// 문서가 이름 있는 벡터 공간에 가진 벡터를 반환합니다.
+    pub fn named_vector(&self, id: &u64, space: &str) -> Option<&Vec<f32>> {
+        self.vector_spaces.get(space)?.get(id)
+    }
+
+    /// 이름 있는 벡터 공간에서 검색합니다. 결과는 (ID, 거리)이며 거리가 작은 순입니다.
+    pub fn search_space(&self, space: &str, query_vector: &[f32], top_k: usize) -> Result<Vec<(u64, f32)>, VectorEngineError> {
+        self.vector_spaces
+            .get(space)
+            .ok_or_else(|| Self::space_not_found(space))?
+            .search(query_vector, top_k)
+    }
+
+    /// 문서에 지정된 희소 벡터를 반환합니다.
+    pub fn sparse_vector(&self, id: &u64) -> Option<&SparseVector> {
+        self.sparse_index.get(id)
+    }
+
+    /// 기존 문서에 희소 벡터를 지정합니다. 밀집 벡터와 HNSW 인덱스는 건드리지 않습니다.
+    ///
+    /// 직접 만든 값도 `SparseVector::new`와 같이 정렬·병합된 형태로 정규화하여 저장합니다.
+    pub fn set_sparse_vector(&mut self, id: &u64, sparse: SparseVector) -> Result<(), VectorEngineError> {
+        self.ensure_exists(id)?;
+        let (index_count, value_count) = (sparse.indices.len(), sparse.values.len());
+        let sparse = SparseVector::new(sparse.indices, sparse.values).ok_or_else(|| {
+            VectorEngineError::DimensionMismatch(format!(
+                "희소 벡터의 인덱스 수({})와 값의 수({})가 일치하지 않습니다.",
+                index_count, value_count
+            ))
+        })?;
+        let undo = self.undo_point(&[*id]);
+        self.sparse_index.insert(*id, sparse);
+        self.record_changes(&[*id], undo)
+    }
+
+    /// 문서(청크)가 속한 그룹을 반환합니다.
+    pub fn document_group(&self, id: &u64) -> Option<&str> {
+        self.groups.get(id).map(String::as_str)
+    }
+
+    /// 그룹에 속한 청크 ID 목록을 반환합니다.
+    pub fn group_members(&self, group: &str) -> Vec<u64> {
+        let mut members: Vec<u64> = self
+            .group_members
+            .get(group)
+            .map(|m| m.iter().copied().collect())
+            .unwrap_or_default();
+        members.sort_unstable();
+        members
+    }
+
+    /// 하나의 노트를 이루는 여러 청크를 ID 자동 할당으로 추가합니다.
+    ///
+    /// 기존에 같은 그룹에 있던 청크는 유지되며, 인덱스 재구성은 한 번만 수행됩니다.
+    pub fn add_chunks(&mut self, group: &str, vectors: Vec<Vec<f32>>) -> Result<Vec<u64>, VectorEngineError> {
+        let undo = self.undo_point(&[]);
+        let ids = self.insert_vectors(vectors)?;
+        for id in &ids {
+            self.assign_group(*id, group);
+        }
+        self.rebuild_index()?;
+        self.record_changes(&ids, undo)?;
+        Ok(ids)
+    }
+
+    /// 기존 문서의 그룹을 변경합니다. 빈 문자열을 넘기면 그룹에서 제외합니다.
+    pub fn set_document_group(&mut self, id: &u64, group: &str) -> Result<(), VectorEngineError> {
+        self.ensure_exists(id)?;
+        let undo = self.undo_point(&[*id]);
+        self.assign_group(*id, group);
+        self.record_changes(&[*id], undo)
+    }
+
+    /// 그룹 맵과 그룹별 구성원 목록을 함께 갱신
+    fn assign_group(&mut self, id: u64, group: &str) {
+        if let Some(old_group) = self.groups.remove(&id)
+            && let Some(members) = self.group_members.get_mut(&old_group)
+        {
+            members.remove(&id);
+            if members.is_empty() {
+                self.group_members.remove(&old_group);
+            }
+        }
+        if !group.is_empty() {
+            self.group_members.entry(group.to_string()).or_default().insert(id);
+            self.groups.insert(id, group.to_string());
+        }
+    }
+
+    /// 경로와 함께 문서를 추가합니다.
+    pub fn add_document_with_path(
+        &mut self,
+        id: u64,
+        vector: Vec<f32>,
+        path: &str,
+    ) -> Result<(), VectorEngineError> {
+        self.ensure_dimension(&vector)?;
+        let undo = self.undo_point(&[id]);
+        self.store_vector(id, vector);
+        self.assign_path(id, path);
+        self.rebuild_index()?;
+        self.record_changes(&[id], undo)
+    }
+
+    /// 기존 문서의 경로를 변경합니다. 빈 문자열을 넘기면 경로를 제거합니다.
+    pub fn set_document_path(&mut self, id: &u64, path: &str) -> Result<(), VectorEngineError> {
+        self.ensure_exists(id)?;
+        let undo = self.undo_point(&[*id]);
+        self.assign_path(*id, path);
+        self.record_changes(&[*id], undo)
     }
 
-    /// 엔진에 저장된 documents 해시맵의 불변 참조를 반환합니다.
-    pub fn documents(&self) -> &HashMap<u64, Vec<f32>> {
-        &self.documents
+    /// 경로 맵과 접두사 인덱스를 함께 갱신
+    fn assign_path(&mut self, id: u64, path: &str) {
+        if let Some(old_path) = self.paths.remove(&id) {
+            self.path_index.remove(&old_path, id);
+        }
+        if !path.is_empty() {
+            self.path_index.insert(path, id);
+            self.paths.insert(id, path.to_string());
+        }
     }
 
     pub fn add_document(&mut self, id: u64, vector: Vec<f32>) -> Result<(), VectorEngineError> {
@@ -84,13 +1285,34 @@ impl VectorEngine {
             return Err(VectorEngineError::DimensionMismatch(error_message));
         }
         // documents 해시에 추가
-        self.documents.insert(id, vector);
+        let undo = self.undo_point(&[id]);
+        self.store_vector(id, vector);
 
         self.rebuild_index()?; // 인덱스 리빌딩
 
-        self.query_cache.clear();
+        self.record_changes(&[id], undo)
+    }
 
-        Ok(())
+    /// 이미 같은 ID의 문서가 있으면 AlreadyExists를 반환하는 엄격한 add_document
+    pub fn add_document_strict(&mut self, id: u64, vector: Vec<f32>) -> Result<(), VectorEngineError> {
+        if self.documents.contains_key(&id) {
+            return Err(VectorEngineError::AlreadyExists(format!(
+                "id {}의 문서가 이미 존재합니다.",
+                id
+            )));
+        }
+        self.add_document(id, vector)
+    }
+
+    /// 문서가 없으면 추가하고, 있으면 벡터를 갱신합니다. 경로와 메타데이터는 유지됩니다.
+    pub fn upsert(&mut self, id: u64, vector: Vec<f32>) -> Result<UpsertOutcome, VectorEngineError> {
+        if self.documents.contains_key(&id) {
+            self.update_document(&id, vector)?;
+            Ok(UpsertOutcome::Updated)
+        } else {
+            self.add_document(id, vector)?;
+            Ok(UpsertOutcome::Inserted)
+        }
     }
 
     pub fn update_document(
@@ -110,7 +1332,8 @@ impl VectorEngine {
         }
 
         // 2. 해당 ID의 문서가 존재하는지 확인
-        if let Some(vector_in_map) = self.documents.get_mut(&id) {
+        let undo = self.undo_point(&[*id]);
+        if let Some(vector_in_map) = self.documents.get_mut(id) {
             *vector_in_map = new_vector;
         } else {
             let error_msg = format!(
@@ -123,40 +1346,173 @@ impl VectorEngine {
         // 3. 변경된 내용을 HNSW 인덱스에 반영하기 위해 전체를 재구성
         self.rebuild_index()?;
 
-        // 4. 쿼리 캐시 제거
-        self.query_cache.clear();
-
-        Ok(())
+        // 4. 바뀐 벡터의 영향을 받는 쿼리 캐시만 제거하고 WAL에 기록
+        self.record_changes(&[*id], undo)
     }
 
     pub fn delete_document(&mut self, id: &u64) -> Result<(), VectorEngineError> {
-        if self.documents.remove(id).is_none() {
+        let undo = self.undo_point(&[*id]);
+        if !self.remove_document_state(*id) {
             return Err(VectorEngineError::ItemNotFound(format!("ID {} not found", id)));
         }
 
         // 3. HNSW 인덱스 재구성
         self.rebuild_index()?;
 
-        // 4. 캐시 일관성을 위한 쿼리 캐시 초기화
-        self.query_cache.clear();
+        // 4. 삭제된 문서를 결과에 포함한 쿼리 캐시만 제거하고 WAL에 기록
+        self.record_changes(&[*id], undo)
+    }
 
-        Ok(())
+    /// 문서와 부가 정보(경로, 문자열 ID, 메타데이터)를 제거 (인덱스 재구성은 호출자가 담당)
+    fn remove_document_state(&mut self, id: u64) -> bool {
+        if !self.has_document(&id) {
+            return false;
+        }
+        self.documents.remove(&id);
+        self.document_ids.remove(&id);
+        self.assign_path(id, "");
+        self.keys.remove_by_id(id);
+        self.payloads.remove(&id);
+        self.assign_text(id, "");
+        self.assign_group(id, "");
+        self.sparse_index.remove(&id);
+        for space in self.vector_spaces.values_mut() {
+            space.remove(&id);
+        }
+        true
+    }
+
+    /// 그룹에 속한 모든 청크를 한 번에 삭제하고, 삭제된 개수를 반환합니다.
+    pub fn delete_group(&mut self, group: &str) -> Result<usize, VectorEngineError> {
+        let members: Vec<u64> = match self.group_members.get(group) {
+            Some(members) => members.iter().copied().collect(),
+            None => {
+                return Err(VectorEngineError::ItemNotFound(format!(
+                    "그룹 '{}'에 속한 문서가 존재하지 않습니다.",
+                    group
+                )));
+            }
+        };
+        let undo = self.undo_point(&members);
+        for id in &members {
+            self.remove_document_state(*id);
+        }
+        self.rebuild_index()?;
+        self.record_changes(&members, undo)?;
+        Ok(members.len())
     }
 
-    pub fn save_to_bytes(&self) -> Result<Vec<u8>, VectorEngineError> {
-        let documents_to_save: Vec<Document> = self
+    /// 필터를 만족하는 모든 문서를 삭제하고, 삭제된 개수를 반환합니다.
+    ///
+    /// 인덱스 재구성과 쿼리 캐시 무효화는 마지막에 한 번만 수행됩니다.
+    pub fn delete_where(&mut self, filter: &Filter) -> Result<usize, VectorEngineError> {
+        let targets: Vec<u64> = self
             .documents
-            .iter()
-            .map(|(&id, vector)| Document {
-                id,
-                vector: vector.clone(),
-            })
+            .keys()
+            .copied()
+            .filter(|id| self.matches_filter(*id, Some(filter)))
             .collect();
+        if targets.is_empty() {
+            return Ok(0);
+        }
+        let undo = self.undo_point(&targets);
+        for id in &targets {
+            self.remove_document_state(*id);
+        }
+        self.rebuild_index()?;
+        self.record_changes(&targets, undo)?;
+        Ok(targets.len())
+    }
 
-        let current_engine_state = EngineState {
-            format_version: 1,
-            documents: documents_to_save,
-        };
+    /// 필터를 만족하는 모든 문서의 메타데이터에 patch를 병합하고, 변경된 문서 수를 반환합니다.
+    pub fn update_payload_where(&mut self, filter: &Filter, patch: &Payload) -> Result<usize, VectorEngineError> {
+        let targets: Vec<u64> = self
+            .documents
+            .keys()
+            .copied()
+            .filter(|id| self.matches_filter(*id, Some(filter)))
+            .collect();
+        let undo = self.undo_point(&targets);
+        for id in &targets {
+            let payload = self.payloads.entry(*id).or_default();
+            merge_patch(payload, patch);
+            if payload.is_empty() {
+                self.payloads.remove(id);
+            }
+        }
+        // 필터가 걸린 캐시 항목은 메타데이터 변경의 영향을 받을 수 있음
+        self.record_payload_changes(&targets, undo)?;
+        Ok(targets.len())
+    }
+
+    /// 토큰 단위 멀티 벡터 문서의 수를 반환합니다.
+    pub fn token_document_count(&self) -> usize {
+        self.token_documents.len()
+    }
+
+    /// 토큰 단위 멀티 벡터 문서의 토큰 벡터들을 반환합니다.
+    pub fn token_document(&self, id: &u64) -> Option<&Vec<Vec<f32>>> {
+        self.token_documents.get(id)
+    }
+
+    /// 토큰마다 임베딩을 가지는 문서를 추가하거나 교체합니다.
+    ///
+    /// 일반 문서(`documents`)와는 별도의 ID 공간을 사용하며 `search_maxsim`으로만 검색됩니다.
+    pub fn add_token_document(&mut self, id: u64, tokens: Vec<Vec<f32>>) -> Result<(), VectorEngineError> {
+        if tokens.is_empty() {
+            return Err(VectorEngineError::DimensionMismatch(
+                "토큰 벡터가 하나 이상 필요합니다.".to_string(),
+            ));
+        }
+        for token in &tokens {
+            self.ensure_dimension(token)?;
+        }
+        self.append_wal(vec![wal_record::Op::PutTokens(Self::to_token_document(id, &tokens))])?;
+        self.token_documents.insert(id, tokens);
+        self.token_documents.rebuild_index(self.ef_search);
+        self.dirty_token_documents.insert(id);
+        Ok(())
+    }
+
+    pub fn delete_token_document(&mut self, id: &u64) -> Result<(), VectorEngineError> {
+        if self.token_documents.get(id).is_none() {
+            return Err(VectorEngineError::ItemNotFound(format!("ID {} not found", id)));
+        }
+        self.append_wal(vec![wal_record::Op::DeleteTokens(*id)])?;
+        self.token_documents.remove(id);
+        self.token_documents.rebuild_index(self.ef_search);
+        self.dirty_token_documents.insert(*id);
+        Ok(())
+    }
+
+    /// 질의 토큰 벡터들로 late interaction(MaxSim) 검색을 수행합니다.
+    ///
+    /// 질의 토큰마다 `candidates_per_token`개의 가까운 토큰으로 후보 문서를 모은 뒤,
+    /// 질의 토큰별 최대 유사도의 합으로 채점합니다. 결과는 (ID, 점수)이며 점수가 높은 순입니다.
+    pub fn search_maxsim(
+        &self,
+        query_tokens: &[Vec<f32>],
+        top_k: usize,
+        candidates_per_token: usize,
+    ) -> Result<Vec<(u64, f32)>, VectorEngineError> {
+        for token in query_tokens {
+            self.ensure_dimension(token)?;
+        }
+        Ok(self.token_documents.search(query_tokens, top_k, candidates_per_token))
+    }
+
+    /// 엔진 전체 상태를 스냅샷으로 직렬화합니다.
+    ///
+    /// 전체 스냅샷이 모든 변경을 담으므로 델타용 변경 추적을 비우며, 이후의 델타는 이 스냅샷을
+    /// 기준으로 이어집니다.
+    pub fn save_to_bytes(&mut self) -> Result<Vec<u8>, VectorEngineError> {
+        let bytes = self.encode_state()?;
+        self.clear_change_tracking();
+        Ok(bytes)
+    }
+
+    fn encode_state(&self) -> Result<Vec<u8>, VectorEngineError> {
+        let current_engine_state = self.to_state();
 
         let mut buf: Vec<u8> = Vec::new();
         // prost::EncodeError를 VectorEngineError::SerializationError로 변환
@@ -167,10 +1523,277 @@ impl VectorEngine {
         Ok(buf)
     }
 
+    /// 엔진 전체 상태를 저장용 EngineState 메시지로 변환합니다.
+    pub fn to_state(&self) -> EngineState {
+        // 이름 있는 벡터만 가진 문서도 함께 저장
+        let ids: HashSet<u64> = self
+            .documents
+            .keys()
+            .chain(self.vector_spaces.values().flat_map(VectorSpace::ids))
+            .copied()
+            .collect();
+        let documents_to_save: Vec<Document> = ids.into_iter().filter_map(|id| self.document_state(id)).collect();
+
+        let token_documents_to_save: Vec<TokenDocument> = self
+            .token_documents
+            .iter()
+            .map(|(&id, tokens)| Self::to_token_document(id, tokens))
+            .collect();
+
+        let vector_spaces_to_save = self.vector_space_configs();
+
+        let embedding_cache_to_save: Vec<CachedEmbedding> = self
+            .embedding_cache
+            .entries_by_recency()
+            .into_iter()
+            .map(|(_, entry)| entry.clone())
+            .collect();
+
+        EngineState {
+            format_version: 1,
+            next_id: self.next_id,
+            metric: DistanceMetric::from(self.metric) as i32,
+            documents: documents_to_save,
+            token_documents: token_documents_to_save,
+            vector_spaces: vector_spaces_to_save,
+            wal_sequence: self.wal_sequence,
+            generation: self.generation,
+            embedding_cache: embedding_cache_to_save,
+        }
+    }
+
+    fn to_token_document(id: u64, tokens: &[Vec<f32>]) -> TokenDocument {
+        TokenDocument {
+            id,
+            tokens: tokens
+                .iter()
+                .map(|values| TokenVector { values: values.clone() })
+                .collect(),
+        }
+    }
+
+    fn restore_token_document(&mut self, doc: TokenDocument) {
+        let tokens = doc.tokens.into_iter().map(|t| t.values).collect();
+        self.token_documents.insert(doc.id, tokens);
+    }
+
+    fn vector_space_configs(&self) -> Vec<VectorSpaceConfig> {
+        self.vector_spaces
+            .iter()
+            .map(|(name, space)| VectorSpaceConfig {
+                name: name.clone(),
+                dimension: space.dimension() as u32,
+                metric: DistanceMetric::from(space.metric()) as i32,
+            })
+            .collect()
+    }
+
+    /// 마지막 스냅샷 이후 저장되지 않은 변경이 있는지 반환합니다.
+    pub fn has_unsaved_changes(&self) -> bool {
+        !self.dirty_documents.is_empty()
+            || !self.dirty_token_documents.is_empty()
+            || !self.pending_documents.is_empty()
+            || !self.pending_token_documents.is_empty()
+    }
+
+    /// 현재 스냅샷 세대 번호를 반환합니다.
+    pub fn snapshot_generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// 마지막으로 확정된 스냅샷 이후 바뀐 문서만 담은 델타 스냅샷을 만듭니다.
+    ///
+    /// 만든 델타를 저장한 뒤 `commit_delta`를 호출해야 변경 추적이 비워지고 세대 번호가 오릅니다.
+    /// 저장에 실패해 확정하지 않으면 다음 델타에 같은 변경이 다시 담깁니다.
+    /// 델타는 같은 세대의 기준 스냅샷(또는 직전 델타까지 적용한 상태)에만 `apply_delta`로 적용할 수
+    /// 있습니다. 이름 있는 벡터 공간 설정은 작으므로 매번 전체가 담기며, 임베딩 캐시는 담기지 않습니다.
+    pub fn save_delta(&mut self) -> Result<Vec<u8>, VectorEngineError> {
+        self.pending_documents.extend(self.dirty_documents.drain());
+        self.pending_token_documents.extend(self.dirty_token_documents.drain());
+        let mut ids: Vec<u64> = self.pending_documents.iter().copied().collect();
+        ids.sort_unstable();
+        let (upserts, deletes): (Vec<u64>, Vec<u64>) = ids.into_iter().partition(|id| self.has_document(id));
+        let mut token_ids: Vec<u64> = self.pending_token_documents.iter().copied().collect();
+        token_ids.sort_unstable();
+        let (token_upserts, token_deletes): (Vec<u64>, Vec<u64>) =
+            token_ids.into_iter().partition(|id| self.token_documents.get(id).is_some());
+
+        let delta = DeltaSnapshot {
+            format_version: 1,
+            base_generation: self.generation,
+            generation: self.generation + 1,
+            next_id: self.next_id,
+            wal_sequence: self.wal_sequence,
+            upserts: upserts.iter().filter_map(|id| self.document_state(*id)).collect(),
+            deletes,
+            token_upserts: token_upserts
+                .iter()
+                .filter_map(|id| self.token_documents.get(id).map(|tokens| Self::to_token_document(*id, tokens)))
+                .collect(),
+            token_deletes,
+            vector_spaces: self.vector_space_configs(),
+        };
+        Ok(delta.encode_to_vec())
+    }
+
+    /// 가장 최근에 `save_delta`로 만든 델타가 저장되었음을 확정합니다.
+    ///
+    /// `generation`은 그 델타의 세대 번호(`snapshot_generation() + 1`)여야 하며, 델타를 만든 뒤의 변경은
+    /// 다음 델타로 넘어갑니다.
+    pub fn commit_delta(&mut self, generation: u64) -> Result<(), VectorEngineError> {
+        if generation != self.generation + 1 {
+            return Err(VectorEngineError::InvalidInput(format!(
+                "확정할 델타의 세대({})가 다음 세대({})와 일치하지 않습니다.",
+                generation,
+                self.generation + 1
+            )));
+        }
+        self.pending_documents.clear();
+        self.pending_token_documents.clear();
+        self.generation = generation;
+        Ok(())
+    }
+
+    /// 전체 스냅샷이 모든 변경을 담았으므로 델타용 변경 추적을 비웁니다.
+    fn clear_change_tracking(&mut self) {
+        self.dirty_documents.clear();
+        self.dirty_token_documents.clear();
+        self.pending_documents.clear();
+        self.pending_token_documents.clear();
+    }
+
+    /// 델타를 모두 합친 전체 스냅샷을 만들고 변경 추적을 초기화합니다. (주기적인 압축용, `save_to_bytes`와 같음)
+    ///
+    /// 이후의 델타는 이 스냅샷을 기준으로 이어집니다.
+    pub fn compact(&mut self) -> Result<Vec<u8>, VectorEngineError> {
+        self.save_to_bytes()
+    }
+
+    /// `save_delta`로 만든 델타 스냅샷을 현재 상태에 적용합니다.
+    ///
+    /// 델타의 기준 세대가 현재 세대와 다르거나(중간 델타가 빠졌거나 순서가 바뀐 경우) 담긴 문서를
+    /// 반영할 수 없으면 아무것도 바꾸지 않고 오류를 반환합니다.
+    pub fn apply_delta(&mut self, bytes: &[u8]) -> Result<(), VectorEngineError> {
+        let delta = DeltaSnapshot::decode(bytes)?;
+        if delta.base_generation != self.generation {
+            return Err(VectorEngineError::DeserializationError(format!(
+                "델타 스냅샷의 기준 세대({})가 현재 세대({})와 일치하지 않습니다.",
+                delta.base_generation, self.generation
+            )));
+        }
+        self.validate_delta(&delta)?;
+
+        for config in &delta.vector_spaces {
+            let metric = Metric::from(config.metric());
+            let dimension = config.dimension as usize;
+            // 삭제 후 다른 설정으로 다시 만든 공간은 델타의 설정으로 새로 만듦
+            let outdated = self
+                .vector_spaces
+                .get(&config.name)
+                .is_none_or(|space| space.dimension() != dimension || space.metric() != metric);
+            if outdated {
+                self.vector_spaces
+                    .insert(config.name.clone(), VectorSpace::new(&config.name, dimension, metric));
+            }
+        }
+        for id in delta.deletes {
+            self.remove_document_state(id);
+        }
+        for doc in delta.upserts {
+            self.restore_document(doc)?;
+        }
+        let spaces: HashSet<&str> = delta.vector_spaces.iter().map(|config| config.name.as_str()).collect();
+        self.vector_spaces.retain(|name, _| spaces.contains(name.as_str()));
+        for id in delta.token_deletes {
+            self.token_documents.remove(&id);
+        }
+        for doc in delta.token_upserts {
+            self.restore_token_document(doc);
+        }
+
+        self.next_id = self.next_id.max(delta.next_id);
+        self.wal_sequence = self.wal_sequence.max(delta.wal_sequence);
+        self.generation = delta.generation;
+        self.reset_after_restore()
+    }
+
+    /// 델타에 담긴 문서들의 벡터 차원, 벡터 공간, 메타데이터를 적용 전에 모두 검사
+    fn validate_delta(&self, delta: &DeltaSnapshot) -> Result<(), VectorEngineError> {
+        // 적용 후의 공간별 차원 (공간 설정은 델타의 것을 따름)
+        let space_dimensions: HashMap<&str, usize> = delta
+            .vector_spaces
+            .iter()
+            .map(|config| (config.name.as_str(), config.dimension as usize))
+            .collect();
+        for doc in &delta.upserts {
+            if !doc.vector.is_empty() {
+                self.ensure_dimension(&doc.vector)?;
+            }
+            if !doc.payload.is_empty() {
+                serde_json::from_str::<Payload>(&doc.payload)
+                    .map_err(|e| VectorEngineError::DeserializationError(e.to_string()))?;
+            }
+            for named in &doc.named_vectors {
+                let dimension = *space_dimensions.get(named.name.as_str()).ok_or_else(|| {
+                    VectorEngineError::DeserializationError(format!(
+                        "문서 {}가 존재하지 않는 벡터 공간 '{}'을 참조합니다.",
+                        doc.id, named.name
+                    ))
+                })?;
+                if dimension != named.vector.len() {
+                    return Err(VectorEngineError::DimensionMismatch(format!(
+                        "문서 {}의 벡터 차원({})이 벡터 공간 '{}'의 차원({})과 일치하지 않습니다.",
+                        doc.id,
+                        named.vector.len(),
+                        named.name,
+                        dimension
+                    )));
+                }
+            }
+        }
+        for doc in &delta.token_upserts {
+            for token in &doc.tokens {
+                self.ensure_dimension(&token.values)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// HNSW 검색 시 유지하는 후보 수를 반환합니다.
+    pub fn ef_search(&self) -> usize {
+        self.ef_search
+    }
+
+    /// HNSW 검색 시 유지하는 후보 수를 바꾸고 인덱스를 재구성합니다. 클수록 정확하지만 느려집니다.
+    pub fn set_ef_search(&mut self, ef_search: usize) -> Result<(), VectorEngineError> {
+        self.ef_search = ef_search.max(1);
+        self.token_documents.rebuild_index(self.ef_search);
+        self.rebuild_index()
+    }
+
     pub fn search(
         &mut self,
-        query_vector: &Vec<f32>,
+        query_vector: &[f32],
         top_k: usize,
+    ) -> Result<Vec<(u64, f32)>, VectorEngineError> {
+        self.search_filtered(query_vector, top_k, None)
+    }
+
+    /// 쿼리 캐시를 읽거나 갱신하지 않는 읽기 전용 검색입니다.
+    pub fn search_uncached(&self, query_vector: &[f32], top_k: usize) -> Result<Vec<(u64, f32)>, VectorEngineError> {
+        self.ensure_dimension(query_vector)?;
+        Ok(self.run_search(query_vector, top_k, None))
+    }
+
+    /// 메타데이터/경로 필터를 적용한 벡터 검색입니다. 결과는 쿼리 캐시에 저장됩니다.
+    ///
+    /// 캐시 키는 질의 벡터, ef_search, 필터, 거리 계산 방식의 지문이며, 적중 시 저장된 조건과
+    /// 다시 비교합니다. 같은 조건으로 더 큰 top_k를 검색한 적이 있으면 그 결과의 앞부분을 사용합니다.
+    pub fn search_filtered(
+        &mut self,
+        query_vector: &[f32],
+        top_k: usize,
+        filter: Option<&Filter>,
     ) -> Result<Vec<(u64, f32)>, VectorEngineError> {
         // 1. 차원 검사
         if self.dimension != query_vector.len() {
@@ -183,33 +1806,459 @@ impl VectorEngine {
         }
 
         // 2. 캐시 키 생성
-        let hash_id: u64 = hash_vector(query_vector);
+        let query = self.cached_query(query_vector, filter);
+        let fingerprint = query.fingerprint();
 
-        // 3. 캐시 검색 (Hit)
-        if let Some(cached_results) = self.query_cache.get(&hash_id) {
-            return Ok(cached_results.clone());
+        // 3. 캐시 검색 (Hit): 조건이 완전히 같고 top_k를 감당할 수 있는 항목만 사용
+        if let Some(cached) = self
+            .query_cache
+            .get_if(&fingerprint, |entry| entry.query.same_as(&query) && entry.answer(top_k).is_some())
+        {
+            let results = cached.answer(top_k).unwrap_or_default();
+            if let Some(semantic) = self.semantic_cache.as_mut() {
+                semantic.record_exact_hit();
+            }
+            return Ok(results);
+        }
+        // 4. 정확히 같은 질의가 없으면 의미 기반 캐시에서 유사한 질의를 찾음
+        //    (저장된 거리는 다른 질의 벡터 기준이므로 현재 질의로 다시 계산하여 정렬)
+        if let Some(results) = self.semantic_cache.as_mut().and_then(|semantic| semantic.lookup(&query, top_k)) {
+            let ids = results.into_iter().map(|(id, _)| id);
+            return Ok(self.exact_search(&self.point(query_vector), ids, top_k));
         }
         // Cache Miss 로직
 
-        let query_point = MyPoint(query_vector.clone());
-        let mut search = Search::default();
+        // 5. 검색 수행
+        let results = self.run_search(query_vector, top_k, filter);
 
-        // 5. 검색 결과를 (u64, f32) 튜플 형태로 변환
-        let mut results: Vec<(u64, f32)> = self.index.search(&query_point, &mut search)
-            .take(top_k)
-            .map(|results| (*results.value as u64, results.distance))
+        // 6. 캐시에 새로운 검색 결과 저장
+        self.store_search(fingerprint, CachedSearch::new(query, top_k, results.clone()));
+
+        // 7. 최종 결과 반환
+        Ok(results)
+    }
+
+    /// 현재 엔진 설정 기준의 캐시용 질의 조건을 만듭니다.
+    fn cached_query(&self, query_vector: &[f32], filter: Option<&Filter>) -> CachedQuery {
+        CachedQuery {
+            vector: query_vector.to_vec(),
+            ef_search: self.ef_search,
+            filter: filter.cloned(),
+            metric: self.metric,
+        }
+    }
+
+    /// 캐시를 거치지 않고 검색하여 거리 오름차순으로 최대 top_k개를 반환합니다.
+    fn run_search(&self, query_vector: &[f32], top_k: usize, filter: Option<&Filter>) -> Vec<(u64, f32)> {
+        let query_point = self.point(query_vector);
+        let mut results: Vec<(u64, f32)> = match filter {
+            None => self.index_search(&query_point, top_k, |_| true),
+            Some(_) => self.filtered_search(&query_point, top_k, |id| self.matches_filter(id, filter)),
+        };
+        results.sort_by(|a, b| a.1.total_cmp(&b.1));
+        results.truncate(top_k);
+        results
+    }
+
+    /// 검색 결과를 정확 일치 캐시와 (켜져 있다면) 의미 기반 캐시에 저장합니다.
+    fn store_search(&mut self, fingerprint: u64, entry: CachedSearch) {
+        if let Some(semantic) = self.semantic_cache.as_mut() {
+            semantic.insert(entry.clone());
+        }
+        self.query_cache.put(fingerprint, entry);
+    }
+
+    /// BM25 키워드 검색을 수행합니다. 결과는 (ID, BM25 점수)이며 점수가 높은 순입니다.
+    pub fn keyword_search(&self, text: &str, top_k: usize) -> Vec<(u64, f32)> {
+        self.text_index.search(text, top_k)
+    }
+
+    /// BM25 키워드 점수와 벡터 유사도를 가중합으로 결합하여 검색합니다.
+    ///
+    /// 두 점수는 각각 후보 안에서 [0, 1]로 정규화된 뒤 `alpha * 벡터 + (1 - alpha) * 키워드`로
+    /// 합쳐집니다. `alpha = 1.0`이면 순수 벡터 검색, `0.0`이면 순수 키워드 검색과 같습니다.
+    /// 결과는 (ID, 결합 점수)이며 점수가 높은 순입니다.
+    pub fn hybrid_search(
+        &self,
+        text: &str,
+        query_vector: &[f32],
+        top_k: usize,
+        alpha: f32,
+    ) -> Result<Vec<(u64, f32)>, VectorEngineError> {
+        self.ensure_dimension(query_vector)?;
+        let alpha = alpha.clamp(0.0, 1.0);
+        let pool = top_k.saturating_mul(CANDIDATE_POOL_FACTOR);
+
+        let query_point = self.point(query_vector);
+        let vector_scores: Vec<(u64, f32)> = self
+            .index_search(&query_point, pool, |_| true)
+            .into_iter()
+            .map(|(id, distance)| (id, self.metric.similarity(distance)))
+            .collect();
+        let keyword_scores = self.text_index.search(text, pool);
+
+        let mut results = weighted_fusion(
+            &normalize_min_max(&vector_scores),
+            &normalize_min_max(&keyword_scores),
+            alpha,
+        );
+        results.truncate(top_k);
+        Ok(results)
+    }
+
+    /// MMR(Maximal Marginal Relevance)로 중복을 줄인 검색 결과를 반환합니다.
+    ///
+    /// HNSW에서 `fetch_k`개의 후보를 가져온 뒤, `lambda * 질의 유사도 - (1 - lambda) * 이미 고른
+    /// 결과와의 최대 유사도`가 가장 큰 문서를 하나씩 고릅니다. `lambda = 1.0`이면 일반 검색과 같고,
+    /// 낮출수록 다양성이 커집니다. 결과는 선택된 순서의 (ID, 질의와의 거리)입니다.
+    pub fn search_mmr(
+        &self,
+        query_vector: &[f32],
+        top_k: usize,
+        fetch_k: usize,
+        lambda: f32,
+    ) -> Result<Vec<(u64, f32)>, VectorEngineError> {
+        self.ensure_dimension(query_vector)?;
+        let lambda = lambda.clamp(0.0, 1.0);
+        let query_point = self.point(query_vector);
+
+        let candidates: Vec<(u64, f32, MetricPoint)> = self
+            .index_search(&query_point, fetch_k.max(top_k), |_| true)
+            .into_iter()
+            .map(|(id, distance)| (id, distance, self.point(&self.documents[&id])))
+            .collect();
+
+        let mut selected: Vec<usize> = Vec::with_capacity(top_k);
+        // 각 후보가 지금까지 선택된 결과들과 가지는 최대 유사도
+        let mut max_redundancy = vec![f32::NEG_INFINITY; candidates.len()];
+        let mut remaining: Vec<usize> = (0..candidates.len()).collect();
+
+        while selected.len() < top_k && !remaining.is_empty() {
+            let score = |i: usize| {
+                let relevance = self.metric.similarity(candidates[i].1);
+                let redundancy = if selected.is_empty() { 0.0 } else { max_redundancy[i] };
+                lambda * relevance - (1.0 - lambda) * redundancy
+            };
+            let position = (0..remaining.len())
+                .max_by(|&a, &b| score(remaining[a]).total_cmp(&score(remaining[b])))
+                .unwrap();
+            let best = remaining.swap_remove(position);
+            selected.push(best);
+
+            for &i in &remaining {
+                let similarity = self.metric.similarity(candidates[i].2.distance(&candidates[best].2));
+                max_redundancy[i] = max_redundancy[i].max(similarity);
+            }
+        }
+
+        Ok(selected
+            .into_iter()
+            .map(|i| (candidates[i].0, candidates[i].1))
+            .collect())
+    }
+
+    /// 저장된 문서의 벡터로 비슷한 문서를 찾습니다. 원본 문서는 결과에서 제외됩니다.
+    ///
+    /// `group_key`를 지정하면 메타데이터의 해당 필드 값이 원본과 같은 문서(같은 노트의
+    /// 다른 청크 등)도 함께 제외합니다.
+    pub fn search_by_id(
+        &self,
+        id: &u64,
+        top_k: usize,
+        group_key: Option<&str>,
+    ) -> Result<Vec<(u64, f32)>, VectorEngineError> {
+        let vector = self.documents.get(id).ok_or_else(|| {
+            VectorEngineError::ItemNotFound(format!(
+                "입력한 id {}에 맞는 문서가 존재하지 않아 검색할 수 없습니다.",
+                id
+            ))
+        })?;
+        let group_value = group_key
+            .and_then(|key| self.payloads.get(id).and_then(|p| p.get(key)).map(|v| (key, v)));
+        let same_group = |other: u64| {
+            group_value.is_some_and(|(key, value)| {
+                self.payloads.get(&other).and_then(|p| p.get(key)) == Some(value)
+            })
+        };
+
+        let query_point = self.point(vector);
+        Ok(self.filtered_search(&query_point, top_k, |other| other != *id && !same_group(other)))
+    }
+
+    /// 긍정 예시와 비슷하고 부정 예시와는 다른 문서를 추천합니다. 예시 문서는 결과에서 제외됩니다.
+    ///
+    /// 결과는 (ID, 거리)이며 search와 마찬가지로 값이 작을수록 추천도가 높습니다.
+    /// `BestScore`의 거리는 `1 - 점수`로, 부정 예시에 더 가까운 후보는 1보다 큰 값을 가집니다.
+    /// 긍정 예시가 없으면 빈 결과를 반환합니다.
+    pub fn recommend(
+        &self,
+        positive_ids: &[u64],
+        negative_ids: &[u64],
+        top_k: usize,
+        strategy: RecommendStrategy,
+    ) -> Result<Vec<(u64, f32)>, VectorEngineError> {
+        let lookup = |ids: &[u64]| -> Result<Vec<MetricPoint>, VectorEngineError> {
+            ids.iter()
+                .map(|id| {
+                    self.documents.get(id).map(|v| self.point(v)).ok_or_else(|| {
+                        VectorEngineError::ItemNotFound(format!("예시 문서 id {}가 존재하지 않습니다.", id))
+                    })
+                })
+                .collect()
+        };
+        let positives = lookup(positive_ids)?;
+        let negatives = lookup(negative_ids)?;
+        if positives.is_empty() {
+            return Ok(Vec::new());
+        }
+        let is_example = |id: u64| positive_ids.contains(&id) || negative_ids.contains(&id);
+
+        match strategy {
+            RecommendStrategy::AverageVector => {
+                let average = |points: &[MetricPoint]| -> Vec<f32> {
+                    let mut sum = vec![0.0; self.dimension];
+                    for point in points {
+                        for (acc, v) in sum.iter_mut().zip(&point.vector) {
+                            *acc += v;
+                        }
+                    }
+                    sum.iter().map(|v| v / points.len() as f32).collect()
+                };
+                let positive_avg = average(&positives);
+                let target: Vec<f32> = if negatives.is_empty() {
+                    positive_avg
+                } else {
+                    let negative_avg = average(&negatives);
+                    positive_avg
+                        .iter()
+                        .zip(&negative_avg)
+                        .map(|(p, n)| p + (p - n))
+                        .collect()
+                };
+                Ok(self.filtered_search(&self.point(&target), top_k, |id| !is_example(id)))
+            }
+            RecommendStrategy::BestScore => {
+                let pool = top_k.saturating_mul(CANDIDATE_POOL_FACTOR);
+                let mut candidates: HashSet<u64> = HashSet::new();
+                for positive in &positives {
+                    candidates.extend(
+                        self.index_search(positive, pool, |id| !is_example(id))
+                            .into_iter()
+                            .map(|(id, _)| id),
+                    );
+                }
+
+                let best_similarity = |point: &MetricPoint, examples: &[MetricPoint]| {
+                    examples
+                        .iter()
+                        .map(|example| self.metric.similarity(point.distance(example)))
+                        .fold(f32::NEG_INFINITY, f32::max)
+                };
+                let mut results: Vec<(u64, f32)> = candidates
+                    .into_iter()
+                    .map(|id| {
+                        let point = self.point(&self.documents[&id]);
+                        let best_positive = best_similarity(&point, &positives);
+                        let best_negative = best_similarity(&point, &negatives);
+                        let score = if best_positive > best_negative { best_positive } else { -best_negative };
+                        (id, 1.0 - score)
+                    })
+                    .collect();
+                results.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+                results.truncate(top_k);
+                Ok(results)
+            }
+        }
+    }
+
+    /// 하나의 질문에서 나온 여러 질의 벡터(바꿔 쓰기, HyDE 등)로 검색하고 결과를 융합합니다.
+    ///
+    /// 질의마다 HNSW는 한 번씩만 탐색해 후보를 모으고, 합쳐진 후보 집합을 모든 질의에 대해
+    /// 같은 기준으로 다시 채점합니다. 캐시는 사용하지 않습니다.
+    /// 결과는 (ID, 융합 점수)이며 점수가 높은 순입니다.
+    pub fn search_multi(
+        &self,
+        queries: &[Vec<f32>],
+        top_k: usize,
+        fusion: FusionMethod,
+    ) -> Result<Vec<(u64, f32)>, VectorEngineError> {
+        for query in queries {
+            self.ensure_dimension(query)?;
+        }
+        let pool = top_k.saturating_mul(CANDIDATE_POOL_FACTOR);
+        let query_points: Vec<MetricPoint> = queries.iter().map(|q| self.point(q)).collect();
+
+        // 1. 질의별 HNSW 탐색 결과를 하나의 후보 집합으로 공유
+        let mut candidates: HashSet<u64> = HashSet::new();
+        for point in &query_points {
+            candidates.extend(self.index_search(point, pool, |_| true).into_iter().map(|(id, _)| id));
+        }
+        let candidate_points: Vec<(u64, MetricPoint)> = candidates
+            .into_iter()
+            .map(|id| (id, self.point(&self.documents[&id])))
             .collect();
 
-        // 5-1. 유사도를 기준으로 높은 순으로 정렬
-        results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        // 2. 공유 후보 전체를 질의마다 유사도로 채점
+        let lists: Vec<Vec<(u64, f32)>> = query_points
+            .iter()
+            .map(|query| {
+                let mut scored: Vec<(u64, f32)> = candidate_points
+                    .iter()
+                    .map(|(id, point)| (*id, self.metric.similarity(query.distance(point))))
+                    .collect();
+                scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+                scored
+            })
+            .collect();
 
-        // 5-2. 결과 개수를 top_k개로 잘라냄
+        let mut results = fuse(&lists, fusion);
         results.truncate(top_k);
+        Ok(results)
+    }
 
-        // 6. 캐시에 새로운 검색 결과 저장
-        self.query_cache.put(hash_id, results.clone());
+    /// 청크 단위로 검색한 뒤 그룹(노트)별로 묶어 상위 `group_count`개 그룹을 반환합니다.
+    ///
+    /// 각 그룹에는 질의와 가장 가까운 청크가 최대 `chunks_per_group`개 포함되며, 그룹 점수는
+    /// 그 청크들의 유사도(`Metric::similarity`)를 `aggregate` 방식으로 합친 값입니다. 그룹이 없는 문서는 제외됩니다.
+    pub fn search_groups(
+        &self,
+        query_vector: &[f32],
+        group_count: usize,
+        chunks_per_group: usize,
+        aggregate: GroupAggregate,
+    ) -> Result<Vec<GroupHit>, VectorEngineError> {
+        self.ensure_dimension(query_vector)?;
+        if group_count == 0 || chunks_per_group == 0 {
+            return Ok(Vec::new());
+        }
+        let pool = group_count
+            .saturating_mul(chunks_per_group)
+            .saturating_mul(CANDIDATE_POOL_FACTOR);
+        let query_point = self.point(query_vector);
+        let mut chunks = self.filtered_search(&query_point, pool, |id| self.groups.contains_key(&id));
+        // 몇몇 그룹의 청크가 후보를 독차지해 그룹 수가 모자라면, 그룹이 있는 청크 전체를 정확히 검색
+        let distinct: HashSet<&str> = chunks.iter().map(|(id, _)| self.groups[id].as_str()).collect();
+        if distinct.len() < group_count && chunks.len() < self.groups.len() {
+            chunks = self.exact_search(&query_point, self.groups.keys().copied(), usize::MAX);
+        }
 
-        // 7. 최종 결과 반환
+        // 거리 오름차순으로 정렬된 청크를 그룹별로 분배
+        let mut grouped: HashMap<&str, Vec<(u64, f32)>> = HashMap::new();
+        for (id, distance) in chunks {
+            let members = grouped.entry(self.groups[&id].as_str()).or_default();
+            if members.len() < chunks_per_group {
+                members.push((id, distance));
+            }
+        }
+
+        let mut hits: Vec<GroupHit> = grouped
+            .into_iter()
+            .map(|(group, chunks)| {
+                let similarities = chunks.iter().map(|(_, distance)| self.metric.similarity(*distance));
+                let score = match aggregate {
+                    GroupAggregate::Max => similarities.fold(f32::NEG_INFINITY, f32::max),
+                    GroupAggregate::Mean => similarities.sum::<f32>() / chunks.len() as f32,
+                };
+                GroupHit { group: group.to_string(), score, chunks }
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.group.cmp(&b.group)));
+        hits.truncate(group_count);
+        Ok(hits)
+    }
+
+    /// 희소 벡터 내적으로 검색합니다. 결과는 (ID, 내적)이며 값이 큰 순입니다.
+    pub fn sparse_search(&self, query: &SparseVector, top_k: usize) -> Vec<(u64, f32)> {
+        self.sparse_index.search(query, top_k)
+    }
+
+    /// 희소 벡터 내적과 밀집 벡터 유사도를 가중합으로 결합하여 검색합니다.
+    ///
+    /// 점수 정규화와 결합 방식은 hybrid_search와 같으며, `alpha`는 밀집 벡터 쪽 가중치입니다.
+    /// 결과는 (ID, 결합 점수)이며 점수가 높은 순입니다.
+    pub fn hybrid_sparse_search(
+        &self,
+        dense_query: &[f32],
+        sparse_query: &SparseVector,
+        top_k: usize,
+        alpha: f32,
+    ) -> Result<Vec<(u64, f32)>, VectorEngineError> {
+        self.ensure_dimension(dense_query)?;
+        let alpha = alpha.clamp(0.0, 1.0);
+        let pool = top_k.saturating_mul(CANDIDATE_POOL_FACTOR);
+
+        let query_point = self.point(dense_query);
+        let dense_scores: Vec<(u64, f32)> = self
+            .index_search(&query_point, pool, |_| true)
+            .into_iter()
+            .map(|(id, distance)| (id, self.metric.similarity(distance)))
+            .collect();
+        let sparse_scores = self.sparse_index.search(sparse_query, pool);
+
+        let mut results = weighted_fusion(
+            &normalize_min_max(&dense_scores),
+            &normalize_min_max(&sparse_scores),
+            alpha,
+        );
+        results.truncate(top_k);
+        Ok(results)
+    }
+
+    /// 검색 결과를 문자열 ID로 반환합니다. 문자열 ID가 없는 문서는 결과에서 제외됩니다.
+    pub fn search_keys(
+        &self,
+        query_vector: &[f32],
+        top_k: usize,
+    ) -> Result<Vec<(String, f32)>, VectorEngineError> {
+        self.ensure_dimension(query_vector)?;
+        let query_point = self.point(query_vector);
+        let results = self.filtered_search(&query_point, top_k, |id| self.keys.key_of(id).is_some());
+        Ok(results
+            .into_iter()
+            .filter_map(|(id, distance)| self.keys.key_of(id).map(|key| (key.to_string(), distance)))
+            .collect())
+    }
+
+    /// 경로 접두사로 범위를 제한하여 검색합니다.
+    ///
+    /// 범위가 좁으면 해당 문서들만 전수 비교하고, 넓으면 HNSW 결과를 걸러냅니다.
+    /// 필터링 후 결과가 부족하면 전수 비교로 보완합니다. 범위 검색은 캐시하지 않습니다.
+    pub fn search_scoped(
+        &self,
+        query_vector: &[f32],
+        top_k: usize,
+        scope: &PathScope,
+    ) -> Result<Vec<(u64, f32)>, VectorEngineError> {
+        self.ensure_dimension(query_vector)?;
+
+        let included: Option<HashSet<u64>> = if scope.include.is_empty() {
+            None
+        } else {
+            Some(self.path_index.ids_with_any_prefix(&scope.include))
+        };
+        let excluded = self.path_index.ids_with_any_prefix(&scope.exclude);
+        let accept = |id: u64| {
+            !excluded.contains(&id) && included.as_ref().is_none_or(|set| set.contains(&id))
+        };
+
+        let eligible: Vec<u64> = match &included {
+            Some(set) => set.iter().copied().filter(|id| accept(*id)).collect(),
+            None => self.documents.keys().copied().filter(|id| accept(*id)).collect(),
+        };
+        let wanted = top_k.min(eligible.len());
+        let query_point = self.point(query_vector);
+
+        // 범위가 전체의 일부에 불과하면 전수 비교가 더 정확하고 저렴함
+        if eligible.len() * 4 <= self.documents.len() {
+            return Ok(self.exact_search(&query_point, eligible, top_k));
+        }
+
+        let mut results = self.index_search(&query_point, top_k, accept);
+        if results.len() < wanted {
+            results = self.exact_search(&query_point, eligible, top_k);
+        }
+        results.sort_by(|a, b| a.1.total_cmp(&b.1));
+        results.truncate(top_k);
         Ok(results)
     }
 
@@ -218,13 +2267,59 @@ impl VectorEngine {
         dimension: usize,
     ) -> Result<Self, VectorEngineError> {
         let state = EngineState::decode(bytes)?;
-        let documents: HashMap<u64, Vec<f32>> = state.documents.into_iter()
-            .map(|doc| (doc.id, doc.vector))
-            .collect();
+        Self::from_state(state, dimension)
+    }
+
+    /// 기준 스냅샷을 불러온 뒤 델타 스냅샷들을 저장된 순서대로 적용합니다.
+    pub fn load_with_deltas<D: AsRef<[u8]>>(
+        base: &[u8],
+        deltas: &[D],
+        dimension: usize,
+    ) -> Result<Self, VectorEngineError> {
+        let mut engine = Self::load_from_bytes(base, dimension)?;
+        for delta in deltas {
+            engine.apply_delta(delta.as_ref())?;
+        }
+        Ok(engine)
+    }
+
+    /// 스냅샷을 불러온 뒤 그 이후에 쌓인 WAL을 재생하여 엔진을 복구합니다.
+    pub fn load_with_wal(
+        snapshot: &[u8],
+        log: &[u8],
+        dimension: usize,
+    ) -> Result<(Self, WalReplay), VectorEngineError> {
+        let mut engine = Self::load_from_bytes(snapshot, dimension)?;
+        let replay = engine.replay_wal(log)?;
+        Ok((engine, replay))
+    }
 
-        let mut engine = Self::new(dimension);
-        engine.documents = documents;
+    /// EngineState 메시지로부터 엔진을 복원합니다. 거리 계산 방식은 상태에 저장된 값을 따릅니다.
+    pub fn from_state(state: EngineState, dimension: usize) -> Result<Self, VectorEngineError> {
+        let mut engine = Self::with_metric(dimension, Metric::from(state.metric()));
+        engine.next_id = state.next_id;
+        engine.wal_sequence = state.wal_sequence;
+        engine.generation = state.generation;
+        for config in &state.vector_spaces {
+            let metric = Metric::from(config.metric());
+            engine.create_vector_space(&config.name, config.dimension as usize, metric)?;
+        }
+        for doc in state.documents {
+            engine.restore_document(doc)?;
+        }
+        for doc in state.token_documents {
+            engine.restore_token_document(doc);
+        }
+        // 원문이 없는(검증할 수 없는) 이전 형식의 항목은 버림
+        for cached in state.embedding_cache {
+            if hash_text(&cached.text) != cached.content_hash {
+                continue;
+            }
+            engine.embedding_cache.put(cached.content_hash, cached);
+        }
+        engine.embedding_cache.reset_stats(); // 복원 과정의 저장 횟수는 통계에서 제외
         engine.rebuild_index()?; // 모든 문서를 채운 뒤, 마지막에 한 번만 재구성
+        engine.token_documents.rebuild_index(engine.ef_search);
         Ok(engine)
     }
 }
@@ -6,6 +6,9 @@ pub enum VectorEngineError {
     SerializationError(String),
     DeserializationError(String),
     ItemNotFound(String),
+    AlreadyExists(String),
+    IoError(String),
+    InvalidInput(String),
 }
 
 // prost의 EncodeError를 받으면 우리 SerializationError로 변환하는 방법
@@ -15,6 +18,13 @@ impl From<prost::EncodeError> for VectorEngineError {
     }
 }
 
+// WAL 등 외부 sink에 쓰다가 발생한 입출력 오류
+impl From<std::io::Error> for VectorEngineError {
+    fn from(err: std::io::Error) -> Self {
+        VectorEngineError::IoError(err.to_string())
+    }
+}
+
 // prost의 DecodeError를 받으면 우리 DeserializationError로 변환하는 방법
 impl From<prost::DecodeError> for VectorEngineError {
     fn from(err: prost::DecodeError) -> Self {
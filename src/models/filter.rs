@@ -0,0 +1,69 @@
+use crate::models::{path_index::path_has_prefix, payload::Payload};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 메타데이터와 경로를 대상으로 하는 문서 필터
+///
+/// `key`는 점(.)으로 중첩 필드를 가리킬 수 있습니다. (예: "meta.stars")
+/// JSON으로는 `{ "match": { "key": "tags", "value": "work" } }`처럼 표현됩니다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Filter {
+    /// 값이 같으면 매칭, 필드가 배열이면 원소 중 하나라도 같으면 매칭
+    Match { key: String, value: Value },
+    /// 값이 후보 중 하나와 같으면 매칭
+    Any { key: String, values: Vec<Value> },
+    /// 숫자 필드가 범위 안에 있으면 매칭 (양 끝 포함)
+    Range {
+        key: String,
+        #[serde(default)]
+        gte: Option<f64>,
+        #[serde(default)]
+        lte: Option<f64>,
+    },
+    /// 필드가 존재하면 매칭
+    Exists { key: String },
+    /// 문서 경로가 접두사 아래에 있으면 매칭
+    PathPrefix(String),
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+    Not(Box<Filter>),
+}
+
+/// 점으로 구분된 키를 따라 중첩 필드를 찾습니다.
+fn lookup<'a>(payload: &'a Payload, key: &str) -> Option<&'a Value> {
+    let mut parts = key.split('.');
+    let mut current = payload.get(parts.next()?)?;
+    for part in parts {
+        current = current.as_object()?.get(part)?;
+    }
+    Some(current)
+}
+
+fn value_matches(field: &Value, expected: &Value) -> bool {
+    match field {
+        Value::Array(items) => items.iter().any(|item| item == expected),
+        _ => field == expected,
+    }
+}
+
+impl Filter {
+    /// 문서의 메타데이터와 경로가 필터 조건을 만족하는지 검사합니다.
+    pub fn matches(&self, payload: Option<&Payload>, path: Option<&str>) -> bool {
+        let field = |key: &str| payload.and_then(|p| lookup(p, key));
+        match self {
+            Filter::Match { key, value } => field(key).is_some_and(|f| value_matches(f, value)),
+            Filter::Any { key, values } => {
+                field(key).is_some_and(|f| values.iter().any(|v| value_matches(f, v)))
+            }
+            Filter::Range { key, gte, lte } => field(key)
+                .and_then(Value::as_f64)
+                .is_some_and(|n| gte.is_none_or(|min| n >= min) && lte.is_none_or(|max| n <= max)),
+            Filter::Exists { key } => field(key).is_some(),
+            Filter::PathPrefix(prefix) => path.is_some_and(|p| path_has_prefix(p, prefix)),
+            Filter::And(filters) => filters.iter().all(|f| f.matches(payload, path)),
+            Filter::Or(filters) => filters.iter().any(|f| f.matches(payload, path)),
+            Filter::Not(filter) => !filter.matches(payload, path),
+        }
+    }
+}
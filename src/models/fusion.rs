@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 점수를 [0, 1] 범위로 min-max 정규화합니다. (높을수록 좋은 점수 기준)
+///
+/// 모든 점수가 같으면 전부 1.0으로 취급합니다.
+pub fn normalize_min_max(results: &[(u64, f32)]) -> HashMap<u64, f32> {
+    let min = results.iter().map(|r| r.1).fold(f32::INFINITY, f32::min);
+    let max = results.iter().map(|r| r.1).fold(f32::NEG_INFINITY, f32::max);
+    let range = max - min;
+    results
+        .iter()
+        .map(|&(id, score)| {
+            let normalized = if range > f32::EPSILON { (score - min) / range } else { 1.0 };
+            (id, normalized)
+        })
+        .collect()
+}
+
+/// 두 정규화 점수 맵을 `alpha * a + (1 - alpha) * b`로 합쳐 높은 순으로 정렬합니다.
+///
+/// 한쪽 목록에만 있는 문서는 다른 쪽 점수를 0으로 계산합니다.
+pub fn weighted_fusion(a: &HashMap<u64, f32>, b: &HashMap<u64, f32>, alpha: f32) -> Vec<(u64, f32)> {
+    let mut fused: HashMap<u64, f32> = HashMap::new();
+    for (&id, &score) in a {
+        *fused.entry(id).or_insert(0.0) += alpha * score;
+    }
+    for (&id, &score) in b {
+        *fused.entry(id).or_insert(0.0) += (1.0 - alpha) * score;
+    }
+    let mut results: Vec<(u64, f32)> = fused.into_iter().collect();
+    results.sort_by(|x, y| y.1.total_cmp(&x.1).then(x.0.cmp(&y.0)));
+    results
+}
+
+/// 순위 기반 융합(RRF)에서 상위 순위의 영향력을 완화하는 상수
+pub const RRF_K: f32 = 60.0;
+
+/// 여러 결과 목록을 하나로 합치는 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FusionMethod {
+    /// 각 목록에서의 순위로 `1 / (RRF_K + 순위)`를 더함
+    ReciprocalRank,
+    /// 목록들 중 가장 높은 점수를 사용
+    MaxScore,
+    /// 목록들의 점수를 모두 더함
+    SumScore,
+}
+
+/// 점수가 높은 순으로 정렬된 여러 결과 목록을 융합하여 높은 순으로 반환합니다.
+pub fn fuse(lists: &[Vec<(u64, f32)>], method: FusionMethod) -> Vec<(u64, f32)> {
+    let mut fused: HashMap<u64, f32> = HashMap::new();
+    for list in lists {
+        for (rank, &(id, score)) in list.iter().enumerate() {
+            match method {
+                FusionMethod::ReciprocalRank => {
+                    *fused.entry(id).or_insert(0.0) += 1.0 / (RRF_K + rank as f32 + 1.0);
+                }
+                FusionMethod::MaxScore => {
+                    let entry = fused.entry(id).or_insert(f32::NEG_INFINITY);
+                    *entry = entry.max(score);
+                }
+                FusionMethod::SumScore => {
+                    *fused.entry(id).or_insert(0.0) += score;
+                }
+            }
+        }
+    }
+    let mut results: Vec<(u64, f32)> = fused.into_iter().collect();
+    results.sort_by(|x, y| y.1.total_cmp(&x.1).then(x.0.cmp(&y.0)));
+    results
+}
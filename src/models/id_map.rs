@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+/// 외부 문자열 ID와 내부 u64 ID 사이의 양방향 맵
+#[derive(Default, Debug)]
+pub struct IdMap {
+    key_to_id: HashMap<String, u64>,
+    id_to_key: HashMap<u64, String>,
+}
+
+impl IdMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 키와 ID를 연결합니다. 양쪽에 남아 있던 기존 연결은 끊어집니다.
+    pub fn insert(&mut self, key: &str, id: u64) {
+        self.remove_by_id(id);
+        if let Some(old_id) = self.key_to_id.remove(key) {
+            self.id_to_key.remove(&old_id);
+        }
+        self.key_to_id.insert(key.to_string(), id);
+        self.id_to_key.insert(id, key.to_string());
+    }
+
+    /// 내부 ID에 연결된 키를 제거하고 반환합니다.
+    pub fn remove_by_id(&mut self, id: u64) -> Option<String> {
+        let key = self.id_to_key.remove(&id)?;
+        self.key_to_id.remove(&key);
+        Some(key)
+    }
+
+    pub fn id_of(&self, key: &str) -> Option<u64> {
+        self.key_to_id.get(key).copied()
+    }
+
+    pub fn key_of(&self, id: u64) -> Option<&str> {
+        self.id_to_key.get(&id).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.key_to_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.key_to_id.is_empty()
+    }
+}
@@ -0,0 +1,111 @@
+use crate::models::point::{Metric, MetricPoint};
+use instant_distance::{Builder, HnswMap, Search};
+use std::collections::{HashMap, HashSet};
+
+/// ColBERT 방식처럼 토큰마다 임베딩을 가지는 문서들의 저장소
+///
+/// 후보 생성은 모든 토큰 벡터를 담은 HNSW 인덱스로 수행하고, 최종 점수는
+/// 질의 토큰마다 문서 토큰과의 최대 유사도를 구해 더하는 MaxSim으로 계산합니다.
+/// 거리와 유사도는 엔진과 같은 거리 계산 방식을 따릅니다.
+pub struct LateInteractionStore {
+    metric: Metric,
+    documents: HashMap<u64, Vec<Vec<f32>>>,
+    /// 토큰 벡터 -> 해당 토큰이 속한 문서 ID
+    index: HnswMap<MetricPoint, u64>,
+}
+
+impl Default for LateInteractionStore {
+    fn default() -> Self {
+        Self::new(Metric::default())
+    }
+}
+
+impl LateInteractionStore {
+    pub fn new(metric: Metric) -> Self {
+        LateInteractionStore {
+            metric,
+            documents: HashMap::new(),
+            index: Builder::default().build(Vec::<MetricPoint>::new(), Vec::<u64>::new()),
+        }
+    }
+
+    fn point(&self, vector: &[f32]) -> MetricPoint {
+        MetricPoint { vector: vector.to_vec(), metric: self.metric }
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    pub fn get(&self, id: &u64) -> Option<&Vec<Vec<f32>>> {
+        self.documents.get(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&u64, &Vec<Vec<f32>>)> {
+        self.documents.iter()
+    }
+
+    /// 문서를 추가하거나 교체합니다. 인덱스 재구성은 `rebuild_index`로 따로 수행합니다.
+    pub fn insert(&mut self, id: u64, tokens: Vec<Vec<f32>>) {
+        self.documents.insert(id, tokens);
+    }
+
+    pub fn remove(&mut self, id: &u64) -> Option<Vec<Vec<f32>>> {
+        self.documents.remove(id)
+    }
+
+    /// 모든 토큰 벡터로 HNSW 인덱스를 재생성 (`ef_search`는 엔진 설정을 따름)
+    pub fn rebuild_index(&mut self, ef_search: usize) {
+        let mut points = Vec::new();
+        let mut values = Vec::new();
+        for (&id, tokens) in &self.documents {
+            for token in tokens {
+                points.push(self.point(token));
+                values.push(id);
+            }
+        }
+        self.index = Builder::default().ef_search(ef_search).build(points, values);
+    }
+
+    /// 질의 토큰 하나당 `candidates_per_token`개의 가까운 토큰을 찾아 후보 문서를 모은 뒤,
+    /// MaxSim 점수가 높은 순으로 최대 top_k개의 (ID, 점수)를 반환합니다.
+    pub fn search(&self, query_tokens: &[Vec<f32>], top_k: usize, candidates_per_token: usize) -> Vec<(u64, f32)> {
+        let query_points: Vec<MetricPoint> = query_tokens.iter().map(|q| self.point(q)).collect();
+
+        let mut candidates: HashSet<u64> = HashSet::new();
+        for query in &query_points {
+            let mut search = Search::default();
+            candidates.extend(
+                self.index
+                    .search(query, &mut search)
+                    .take(candidates_per_token)
+                    .map(|item| *item.value),
+            );
+        }
+
+        let mut results: Vec<(u64, f32)> = candidates
+            .into_iter()
+            .map(|id| (id, self.max_sim(query_tokens, &self.documents[&id])))
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        results.truncate(top_k);
+        results
+    }
+
+    /// 질의 토큰별로 문서 토큰과의 최대 유사도(`Metric::similarity`)를 구해 모두 더합니다.
+    fn max_sim(&self, query_tokens: &[Vec<f32>], tokens: &[Vec<f32>]) -> f32 {
+        query_tokens
+            .iter()
+            .map(|query| {
+                tokens
+                    .iter()
+                    .map(|token| self.metric.similarity(self.metric.distance(query, token)))
+                    .fold(f32::NEG_INFINITY, f32::max)
+            })
+            .sum()
+    }
+}
@@ -3,9 +3,35 @@ pub mod engine;
 pub mod search_cache;
 pub mod errors;
 pub mod point;
+pub mod path_index;
+pub mod id_map;
+pub mod payload;
+pub mod filter;
+pub mod record;
+pub mod text_index;
+pub mod fusion;
+pub mod late_interaction;
+pub mod sparse_index;
+pub mod vector_space;
+pub mod database;
+pub mod semantic_cache;
+pub mod wal;
 
-pub use document::Document;
-pub use engine::VectorEngine;
-pub use search_cache::{SearchCache, CacheStats};
+pub use document::{Document, SparseVector};
+pub use engine::{GroupAggregate, RecommendStrategy, UpsertOutcome, VectorEngine};
+pub use search_cache::{CacheConfig, CacheWeight, CachedQuery, CachedSearch, EvictionPolicy, SearchCache, CacheStats};
 pub use errors::VectorEngineError;
-pub use point::MyPoint;
\ No newline at end of file
+pub use point::{Metric, MetricPoint, MyPoint};
+pub use path_index::{PathIndex, PathScope};
+pub use id_map::IdMap;
+pub use payload::Payload;
+pub use filter::Filter;
+pub use record::{DocumentRecord, FederatedHit, GroupHit, IngestReport, ScrollPage};
+pub use text_index::TextIndex;
+pub use fusion::FusionMethod;
+pub use late_interaction::LateInteractionStore;
+pub use sparse_index::SparseIndex;
+pub use vector_space::VectorSpace;
+pub use database::Database;
+pub use semantic_cache::{SemanticCache, SemanticCacheStats};
+pub use wal::{SharedBuffer, WalReplay, WriteAheadLog};
\ No newline at end of file
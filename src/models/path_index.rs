@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// 경로 세그먼트('/' 단위)를 노드로 가지는 트라이 노드
+#[derive(Default, Debug)]
+struct PathNode {
+    children: HashMap<String, PathNode>,
+    ids: HashSet<u64>,
+}
+
+impl PathNode {
+    fn is_empty(&self) -> bool {
+        self.children.is_empty() && self.ids.is_empty()
+    }
+
+    /// 현재 노드를 루트로 하는 하위 트리의 모든 ID를 수집합니다.
+    fn collect_into(&self, out: &mut HashSet<u64>) {
+        out.extend(self.ids.iter().copied());
+        for child in self.children.values() {
+            child.collect_into(out);
+        }
+    }
+}
+
+/// 문서 경로를 세그먼트 단위 트라이로 관리하는 접두사 인덱스
+///
+/// "Projects/2025"는 "Projects/2025/plan.md"와 매칭되지만,
+/// "Projects/2025-old/plan.md"와는 매칭되지 않습니다.
+#[derive(Default, Debug)]
+pub struct PathIndex {
+    root: PathNode,
+}
+
+/// 경로를 비어 있지 않은 세그먼트 목록으로 분리합니다.
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|s| !s.is_empty())
+}
+
+/// `path`가 세그먼트 단위로 `prefix` 아래에 있는지 검사합니다.
+pub fn path_has_prefix(path: &str, prefix: &str) -> bool {
+    let mut path_segments = segments(path);
+    segments(prefix).all(|p| path_segments.next() == Some(p))
+}
+
+impl PathIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 주어진 경로에 ID를 등록합니다.
+    pub fn insert(&mut self, path: &str, id: u64) {
+        let mut node = &mut self.root;
+        for segment in segments(path) {
+            node = node.children.entry(segment.to_string()).or_default();
+        }
+        node.ids.insert(id);
+    }
+
+    /// 주어진 경로에서 ID를 제거하고, 비게 된 노드를 정리합니다.
+    pub fn remove(&mut self, path: &str, id: u64) {
+        let parts: Vec<&str> = segments(path).collect();
+        Self::remove_rec(&mut self.root, &parts, id);
+    }
+
+    fn remove_rec(node: &mut PathNode, parts: &[&str], id: u64) {
+        match parts.split_first() {
+            None => {
+                node.ids.remove(&id);
+            }
+            Some((head, rest)) => {
+                if let Some(child) = node.children.get_mut(*head) {
+                    Self::remove_rec(child, rest, id);
+                    if child.is_empty() {
+                        node.children.remove(*head);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 접두사 아래에 있는 모든 문서 ID를 반환합니다.
+    pub fn ids_with_prefix(&self, prefix: &str) -> HashSet<u64> {
+        let mut out = HashSet::new();
+        let mut node = &self.root;
+        for segment in segments(prefix) {
+            match node.children.get(segment) {
+                Some(child) => node = child,
+                None => return out,
+            }
+        }
+        node.collect_into(&mut out);
+        out
+    }
+
+    /// 여러 접두사에 속하는 문서 ID의 합집합을 반환합니다.
+    pub fn ids_with_any_prefix(&self, prefixes: &[String]) -> HashSet<u64> {
+        let mut out = HashSet::new();
+        for prefix in prefixes {
+            out.extend(self.ids_with_prefix(prefix));
+        }
+        out
+    }
+}
+
+/// 검색 범위를 경로 접두사로 제한하기 위한 조건
+///
+/// `include`가 비어 있으면 전체 문서가 대상이며, `exclude`는 항상 우선합니다.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PathScope {
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl PathScope {
+    pub fn include<S: Into<String>>(prefixes: impl IntoIterator<Item = S>) -> Self {
+        PathScope {
+            include: prefixes.into_iter().map(Into::into).collect(),
+            exclude: Vec::new(),
+        }
+    }
+
+    pub fn exclude<S: Into<String>>(prefixes: impl IntoIterator<Item = S>) -> Self {
+        PathScope {
+            include: Vec::new(),
+            exclude: prefixes.into_iter().map(Into::into).collect(),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use serde_json::{Map, Value};
+
+/// 문서에 붙는 메타데이터 (JSON 객체)
+pub type Payload = Map<String, Value>;
+
+/// RFC 7396 JSON Merge Patch 규칙으로 `patch`를 `target`에 병합합니다.
+///
+/// `null` 값은 해당 키를 삭제하고, 객체끼리는 재귀적으로 병합되며, 나머지 값은 덮어씁니다.
+pub fn merge_patch(target: &mut Payload, patch: &Payload) {
+    for (key, patch_value) in patch {
+        match patch_value {
+            Value::Null => {
+                target.remove(key);
+            }
+            Value::Object(patch_object) => {
+                let entry = target
+                    .entry(key.clone())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if !entry.is_object() {
+                    *entry = Value::Object(Map::new());
+                }
+                if let Value::Object(target_object) = entry {
+                    merge_patch(target_object, patch_object);
+                }
+            }
+            _ => {
+                target.insert(key.clone(), patch_value.clone());
+            }
+        }
+    }
+}
@@ -1,14 +1,64 @@
 use instant_distance::Point as IDPoint;
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug)]
 pub struct MyPoint(pub Vec<f32>);
 
 impl IDPoint for MyPoint {
     fn distance(&self, other: &Self) -> f32 {
-        let dot_product: f32 = self.0.iter().zip(&other.0).map(|(a, b)| a * b).sum();
-        let norm_a: f32 = self.0.iter().map(|a| a.powi(2)).sum::<f32>().sqrt();
-        let norm_b: f32 = other.0.iter().map(|b| b.powi(2)).sum::<f32>().sqrt();
-        if norm_a == 0.0 || norm_b == 0.0 { return 2.0; }
-        1.0 - (dot_product / (norm_a * norm_b))
+        Metric::Cosine.distance(&self.0, &other.0)
     }
-}
\ No newline at end of file
+}
+
+/// 벡터 간 거리 계산 방식 (모두 값이 작을수록 가까움)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Metric {
+    /// `1 - 코사인 유사도`, 범위 [0, 2]
+    #[default]
+    Cosine,
+    /// 유클리드 거리
+    Euclidean,
+    /// 내적의 음수 (내적이 클수록 가까움)
+    DotProduct,
+}
+
+impl Metric {
+    pub fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            Metric::Cosine => {
+                let dot_product: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+                let norm_a: f32 = a.iter().map(|x| x.powi(2)).sum::<f32>().sqrt();
+                let norm_b: f32 = b.iter().map(|y| y.powi(2)).sum::<f32>().sqrt();
+                if norm_a == 0.0 || norm_b == 0.0 { return 2.0; }
+                1.0 - (dot_product / (norm_a * norm_b))
+            }
+            Metric::Euclidean => a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f32>().sqrt(),
+            Metric::DotProduct => -a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>(),
+        }
+    }
+
+    /// 거리를 (0, 1] 범위의 유사도 점수로 변환합니다. (높을수록 가까움)
+    ///
+    /// 거리 계산 방식이 다른 결과끼리 비교할 수 있도록 각 방식의 거리를 같은 척도로 맞춥니다.
+    pub fn similarity(&self, distance: f32) -> f32 {
+        match self {
+            Metric::Cosine => 1.0 - distance / 2.0,
+            Metric::Euclidean => 1.0 / (1.0 + distance),
+            // 거리는 -내적이므로 내적에 시그모이드를 적용
+            Metric::DotProduct => 1.0 / (1.0 + distance.exp()),
+        }
+    }
+}
+
+/// 거리 계산 방식을 함께 지니는 HNSW용 포인트
+#[derive(Clone, Debug)]
+pub struct MetricPoint {
+    pub vector: Vec<f32>,
+    pub metric: Metric,
+}
+
+impl IDPoint for MetricPoint {
+    fn distance(&self, other: &Self) -> f32 {
+        self.metric.distance(&self.vector, &other.vector)
+    }
+}
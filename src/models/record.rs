@@ -0,0 +1,45 @@
+use crate::models::payload::Payload;
+use serde::Serialize;
+
+/// 엔진에 저장된 문서 하나의 사본 (벡터, 메타데이터, 경로, 문자열 ID)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocumentRecord {
+    pub id: u64,
+    pub vector: Vec<f32>,
+    pub payload: Option<Payload>,
+    pub path: Option<String>,
+    pub key: Option<String>,
+    pub group: Option<String>,
+}
+
+/// scroll 한 번의 결과 페이지
+///
+/// `next_cursor`가 `None`이면 더 가져올 문서가 없습니다.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScrollPage {
+    pub documents: Vec<DocumentRecord>,
+    pub next_cursor: Option<u64>,
+}
+
+/// 그룹 검색 결과 하나 (그룹, 집계 점수, 가장 가까운 청크들의 (ID, 거리))
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupHit {
+    pub group: String,
+    pub score: f32,
+    pub chunks: Vec<(u64, f32)>,
+}
+
+/// 여러 컬렉션에 걸친 검색 결과 하나 (출처 컬렉션, 문서 ID, 정규화된 유사도 점수)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FederatedHit {
+    pub collection: String,
+    pub id: u64,
+    pub score: f32,
+}
+
+/// 텍스트 수집 결과 (임베딩 캐시 적중 수, 새로 임베딩한 텍스트 수)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct IngestReport {
+    pub cache_hits: usize,
+    pub embedded: usize,
+}
@@ -1,60 +1,515 @@
-use lru::LruCache;
-use std::num::NonZeroUsize;
+use crate::models::{filter::Filter, point::Metric};
+use crate::utils::now_millis;
+use ahash::AHasher;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
 
-#[derive(Default, Debug)]
+/// 캐시 사용 통계
+///
+/// 항목 무효화나 `SearchCache::clear`로는 초기화되지 않으며, `reset_stats`로만 누적 값을 되돌립니다.
+#[derive(Default, Debug, Clone, PartialEq, Serialize)]
 pub struct CacheStats {
     pub hits: u64,
-    pub misses: u64
+    pub misses: u64,
+    /// 캐시에 새로 저장된 횟수 (같은 키의 교체 포함)
+    pub insertions: u64,
+    /// 용량(항목 수 또는 바이트)을 넘어 제거된 항목 수
+    pub evictions: u64,
+    /// TTL이 지나 제거된 항목 수
+    pub expirations: u64,
+    /// 현재 저장된 항목 수
+    pub entries: usize,
+    /// 현재 저장된 값들의 추정 크기(바이트)
+    pub bytes: usize,
 }
 
-pub struct SearchCache<'a, K: std::hash::Hash + Eq, V> {
-    cache: LruCache<K, V>,
+impl CacheStats {
+    /// 현재 히트율을 백분율(%)로 계산하여 반환합니다.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            (self.hits as f64 / total as f64) * 100.0
+        }
+    }
+}
+
+/// 캐시가 가득 찼을 때 제거할 항목을 고르는 방식
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EvictionPolicy {
+    /// 가장 오래 사용되지 않은 항목부터 제거
+    #[default]
+    Lru,
+    /// 사용 횟수가 가장 적은 항목부터 제거 (같으면 오래된 항목)
+    ///
+    /// 사용 횟수는 시간이 지나도 줄어들지 않으므로, 예전에 많이 쓰인 항목은 더 이상 조회되지 않아도
+    /// 오래 남을 수 있습니다. 횟수는 항목이 제거되거나 같은 키로 다시 저장될 때만 초기화됩니다.
+    Lfu,
+    /// 2Q 방식: 새 항목은 최대 용량의 1/4 크기인 A1in(FIFO)에 들어가고, 조회되면 Am(LRU)으로 옮겨짐
+    ///
+    /// A1in이 가득 차면 A1in에서, 아니면 Am에서 제거합니다. A1in에서 밀려난 키는 용량의 1/2 크기인
+    /// A1out에 키만 기억해 두었다가 다시 저장되면 바로 Am에 넣으므로, 일회성 스캔이 자주 쓰는 항목을
+    /// 밀어내지 않습니다.
+    TwoQueue,
+}
+
+/// 캐시 정책과 용량 설정
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub policy: EvictionPolicy,
+    /// 최대 항목 수 (최소 1)
+    pub max_entries: usize,
+    /// 저장된 값들의 추정 크기 합의 상한 (바이트)
+    pub max_bytes: Option<usize>,
+    /// 저장 후 이 시간(밀리초)이 지나면 만료
+    pub ttl_ms: Option<u64>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            policy: EvictionPolicy::Lru,
+            max_entries: 100,
+            max_bytes: None,
+            ttl_ms: None,
+        }
+    }
+}
+
+/// 바이트 단위 용량 계산에 쓰이는 값의 추정 크기
+pub trait CacheWeight {
+    fn weight(&self) -> usize;
+}
+
+impl<T> CacheWeight for Vec<T> {
+    fn weight(&self) -> usize {
+        std::mem::size_of::<Self>() + self.capacity() * std::mem::size_of::<T>()
+    }
+}
+
+/// 검색 결과를 좌우하는 질의 조건 (top_k 제외)
+///
+/// 캐시 키는 이 값의 지문이며, 적중 시에는 저장된 조건과 전부 비교하여 해시 충돌을 걸러냅니다.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedQuery {
+    pub vector: Vec<f32>,
+    pub ef_search: usize,
+    pub filter: Option<Filter>,
+    pub metric: Metric,
+}
+
+impl CachedQuery {
+    /// 질의 조건 전체를 64비트 지문으로 만듭니다. 벡터는 비트 표현 그대로 해싱합니다.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = AHasher::default();
+        for v in &self.vector {
+            hasher.write_u32(v.to_bits());
+        }
+        hasher.write_usize(self.ef_search);
+        hasher.write_u8(self.metric as u8);
+        if let Some(filter) = &self.filter {
+            hasher.write(serde_json::to_string(filter).unwrap_or_default().as_bytes());
+        }
+        hasher.finish()
+    }
+
+    /// 다른 질의와 조건이 완전히 같은지 비교합니다. 벡터는 비트 단위로 비교합니다.
+    pub fn same_as(&self, other: &CachedQuery) -> bool {
+        self.vector.len() == other.vector.len()
+            && self.vector.iter().zip(&other.vector).all(|(a, b)| a.to_bits() == b.to_bits())
+            && self.ef_search == other.ef_search
+            && self.metric == other.metric
+            && self.filter == other.filter
+    }
+}
+
+/// 검색 결과 캐시 항목
+///
+/// 쓰기 이후 영향을 받는 항목만 골라 무효화할 수 있도록 질의 조건, 요청한 top_k,
+/// 결과 중 가장 먼 거리를 함께 보관합니다.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedSearch {
+    pub query: CachedQuery,
+    pub top_k: usize,
+    pub results: Vec<(u64, f32)>,
+    pub worst_distance: f32,
+}
+
+impl CachedSearch {
+    /// 거리 오름차순으로 정렬된 결과로 캐시 항목을 만듭니다.
+    pub fn new(query: CachedQuery, top_k: usize, results: Vec<(u64, f32)>) -> Self {
+        let worst_distance = results.last().map_or(f32::INFINITY, |r| r.1);
+        CachedSearch { query, top_k, results, worst_distance }
+    }
+
+    /// 같은 조건에서 top_k개를 요청했을 때 이 항목으로 답할 수 있으면 결과를 반환합니다.
+    ///
+    /// 더 큰 top_k로 저장된 결과의 앞부분을 잘라 쓰며, 저장된 결과가 top_k를 채우지 못했다면
+    /// (문서가 그만큼 없었다면) 더 큰 top_k에도 그대로 답합니다.
+    pub fn answer(&self, top_k: usize) -> Option<Vec<(u64, f32)>> {
+        if top_k <= self.top_k || self.results.len() < self.top_k {
+            Some(self.results.iter().take(top_k).copied().collect())
+        } else {
+            None
+        }
+    }
+
+    pub fn contains_id(&self, id: u64) -> bool {
+        self.results.iter().any(|r| r.0 == id)
+    }
+
+    /// 질의와 `distance`만큼 떨어진 새 벡터가 결과에 들어올 수 있는지 확인합니다.
+    ///
+    /// 결과가 top_k개를 채우지 못했다면 어떤 벡터든 들어올 수 있습니다.
+    pub fn admits(&self, distance: f32) -> bool {
+        self.results.len() < self.top_k || distance <= self.worst_distance
+    }
+}
+
+impl CacheWeight for CachedSearch {
+    fn weight(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.query.vector.capacity() * std::mem::size_of::<f32>()
+            + self.results.capacity() * std::mem::size_of::<(u64, f32)>()
+    }
+}
+
+/// 캐시 내부 항목과 정책별 관리 정보
+struct Slot<V> {
+    value: V,
+    weight: usize,
+    inserted_at: u64,
+    /// 저장 순서 (같은 시각에 저장된 항목의 만료 순서를 정함)
+    inserted_seq: u64,
+    /// 마지막 사용 시점 (논리 시계)
+    last_used: u64,
+    /// 조회된 횟수 (LFU에서 사용)
+    frequency: u64,
+    /// 2Q의 Am 큐에 속하는지 여부 (조회되었거나 A1out에서 다시 저장된 항목)
+    hot: bool,
+}
+
+/// 통계와 교체 정책을 갖춘 검색 결과 캐시
+///
+/// 제거 순서는 정책별 순위 `(등급, 마지막 사용 시점)`로 정렬된 트리에, 만료 순서는 저장 시각 순
+/// 트리에 따로 보관하므로 제거와 만료 처리는 항목 수에 대해 O(log n)입니다.
+pub struct SearchCache<'a, K: Hash + Eq, V> {
+    entries: HashMap<K, Slot<V>>,
+    /// 정책별 순위 -> 키 (가장 앞의 항목이 다음 제거 대상)
+    eviction_order: BTreeMap<(u64, u64), K>,
+    /// (저장 시각, 저장 순서) -> 키 (TTL이 모든 항목에 같으므로 가장 앞의 항목이 가장 먼저 만료)
+    expiry_order: BTreeMap<(u64, u64), K>,
+    /// A1in 큐(아직 hot이 아닌 항목)의 크기
+    probation: usize,
+    /// 2Q의 A1out: A1in에서 밀려난 키 -> 기억한 순서
+    ghosts: HashMap<K, u64>,
+    /// 기억한 순서 -> 키 (가장 앞의 키부터 잊음)
+    ghost_order: BTreeMap<u64, K>,
+    config: CacheConfig,
     stats: CacheStats,
+    /// 사용 순서를 매기기 위한 논리 시계
+    tick: u64,
+    /// TTL 계산에 쓰는 현재 시각(밀리초) 공급자
+    clock: fn() -> u64,
+    /// false이면 조회와 저장을 모두 건너뜀
+    enabled: bool,
     _phantom: std::marker::PhantomData<&'a ()>
 }
 
-impl<'a, K: std::hash::Hash + Eq, V> SearchCache<'a, K, V> {
-    /// 통계 기능이 추가된 새 캐시를 생성합니다.
+impl<'a, K: Hash + Eq + Clone, V: CacheWeight> SearchCache<'a, K, V> {
+    /// 통계 기능이 추가된 새 LRU 캐시를 생성합니다.
     pub fn new(capacity: usize) -> Self {
-        // 용량이 0이 되는 것을 방지
-        let non_zero_capacity = NonZeroUsize::new(capacity)
-            .unwrap_or_else(|| NonZeroUsize::new(1).unwrap()); // 0일 경우 기본값 1
+        Self::with_config(CacheConfig { max_entries: capacity, ..CacheConfig::default() })
+    }
 
+    /// 교체 정책, 용량, TTL을 지정하여 캐시를 생성합니다.
+    pub fn with_config(config: CacheConfig) -> Self {
         SearchCache {
-            cache: LruCache::new(non_zero_capacity),
+            entries: HashMap::new(),
+            eviction_order: BTreeMap::new(),
+            expiry_order: BTreeMap::new(),
+            probation: 0,
+            ghosts: HashMap::new(),
+            ghost_order: BTreeMap::new(),
+            config: Self::normalized(config),
             stats: CacheStats::default(),
+            tick: 0,
+            clock: now_millis,
+            enabled: true,
             _phantom: std::marker::PhantomData,
         }
     }
 
-    /// 데이터를 추가합니다. (내부 캐시의 put 호출)
+    /// TTL 계산에 사용할 시계를 바꿉니다. (테스트 등에서 사용)
+    pub fn with_clock(mut self, clock: fn() -> u64) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn normalized(mut config: CacheConfig) -> CacheConfig {
+        // 용량이 0이 되는 것을 방지
+        config.max_entries = config.max_entries.max(1);
+        config
+    }
+
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    /// 설정을 바꾸고, 새 용량을 넘는 항목은 정책에 따라 제거합니다.
+    pub fn set_config(&mut self, config: CacheConfig) {
+        let policy_changed = config.policy != self.config.policy;
+        self.config = Self::normalized(config);
+        if policy_changed {
+            let policy = self.config.policy;
+            self.eviction_order = self
+                .entries
+                .iter()
+                .map(|(key, slot)| (Self::rank(policy, slot), key.clone()))
+                .collect();
+            self.forget_ghosts();
+        }
+        self.purge_expired();
+        self.evict_to_fit(0, false);
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// 정책에 따른 제거 순위. 값이 작을수록 먼저 제거됩니다.
+    fn rank(policy: EvictionPolicy, slot: &Slot<V>) -> (u64, u64) {
+        let class = match policy {
+            EvictionPolicy::Lru => 0,
+            // 사용 횟수가 같으면 오래된 항목부터
+            EvictionPolicy::Lfu => slot.frequency,
+            // A1in 항목(0)은 저장 순서, Am 항목(1)은 마지막 사용 순서로 정렬됨
+            EvictionPolicy::TwoQueue => u64::from(slot.hot),
+        };
+        (class, slot.last_used)
+    }
+
+    fn is_expired(&self, slot: &Slot<V>, now: u64) -> bool {
+        self.config.ttl_ms.is_some_and(|ttl| now.saturating_sub(slot.inserted_at) >= ttl)
+    }
+
+    fn remove_slot(&mut self, key: &K) -> Option<Slot<V>> {
+        let slot = self.entries.remove(key)?;
+        self.eviction_order.remove(&Self::rank(self.config.policy, &slot));
+        self.expiry_order.remove(&(slot.inserted_at, slot.inserted_seq));
+        self.stats.bytes -= slot.weight;
+        self.stats.entries -= 1;
+        if !slot.hot {
+            self.probation -= 1;
+        }
+        Some(slot)
+    }
+
+    /// 2Q에서 다음에 제거할 키. A1in이 정해진 크기에 이르렀거나 Am이 비어 있으면 A1in의 가장 오래된 항목입니다.
+    fn two_queue_victim(&self) -> Option<K> {
+        let limit = (self.config.max_entries / 4).max(1);
+        let from_probation = self.probation >= limit || self.probation == self.entries.len();
+        let mut queue = if from_probation {
+            self.eviction_order.range(..(1, 0))
+        } else {
+            self.eviction_order.range((1, 0)..)
+        };
+        queue.next().map(|(_, key)| key.clone())
+    }
+
+    /// A1in에서 밀려난 키를 A1out에 기억합니다. A1out은 최대 용량의 1/2까지만 유지합니다.
+    fn remember_ghost(&mut self, key: K) {
+        let seq = self.next_tick();
+        self.ghost_order.insert(seq, key.clone());
+        self.ghosts.insert(key, seq);
+        let limit = (self.config.max_entries / 2).max(1);
+        while self.ghosts.len() > limit {
+            let Some((_, oldest)) = self.ghost_order.pop_first() else {
+                break;
+            };
+            self.ghosts.remove(&oldest);
+        }
+    }
+
+    /// A1out에 있던 키면 잊고 true를 반환합니다.
+    fn take_ghost(&mut self, key: &K) -> bool {
+        match self.ghosts.remove(key) {
+            Some(seq) => {
+                self.ghost_order.remove(&seq);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn forget_ghosts(&mut self) {
+        self.ghosts.clear();
+        self.ghost_order.clear();
+    }
+
+    /// 만료된 항목을 모두 제거합니다.
+    pub fn purge_expired(&mut self) -> usize {
+        let Some(ttl) = self.config.ttl_ms else {
+            return 0;
+        };
+        let now = (self.clock)();
+        let mut expired = 0;
+        while let Some((&(inserted_at, _), key)) = self.expiry_order.first_key_value() {
+            if now.saturating_sub(inserted_at) < ttl {
+                break;
+            }
+            let key = key.clone();
+            self.remove_slot(&key);
+            expired += 1;
+        }
+        self.stats.expirations += expired as u64;
+        expired
+    }
+
+    /// 용량 안에 들 때까지 정책 순서대로 항목을 제거합니다.
+    ///
+    /// `inserting`이면 항목 하나와 `incoming` 바이트를 더 넣을 자리를 확보합니다.
+    fn evict_to_fit(&mut self, incoming: usize, inserting: bool) {
+        let reserve = usize::from(inserting);
+        loop {
+            let over_entries = self.entries.len() + reserve > self.config.max_entries;
+            let over_bytes = self
+                .config
+                .max_bytes
+                .is_some_and(|max| self.stats.bytes + incoming > max);
+            if !(over_entries || over_bytes) {
+                return;
+            }
+            let victim = match self.config.policy {
+                EvictionPolicy::TwoQueue => self.two_queue_victim(),
+                _ => self.eviction_order.first_key_value().map(|(_, key)| key.clone()),
+            };
+            let Some(key) = victim else {
+                return;
+            };
+            if let Some(slot) = self.remove_slot(&key)
+                && self.config.policy == EvictionPolicy::TwoQueue
+                && !slot.hot
+            {
+                self.remember_ghost(key);
+            }
+            self.stats.evictions += 1;
+        }
+    }
+
+    /// 데이터를 추가합니다. 같은 키의 기존 값이 있으면 교체하고 반환합니다.
+    ///
+    /// 값 하나가 `max_bytes`보다 크면 저장하지 않습니다.
     pub fn put(&mut self, key: K, value: V) -> Option<V> {
-        self.cache.put(key, value)
+        if !self.enabled {
+            return None;
+        }
+        let previous = self.remove_slot(&key);
+        // 이미 Am에 있던 키나 A1out에 기억된 키는 Am으로 저장
+        let hot = previous.as_ref().is_some_and(|slot| slot.hot) | self.take_ghost(&key);
+        let previous = previous.map(|slot| slot.value);
+        let weight = value.weight();
+        if self.config.max_bytes.is_some_and(|max| weight > max) {
+            return previous;
+        }
+        self.purge_expired();
+        self.evict_to_fit(weight, true);
+
+        let now = (self.clock)();
+        let tick = self.next_tick();
+        let slot = Slot { value, weight, inserted_at: now, inserted_seq: tick, last_used: tick, frequency: 0, hot };
+        self.eviction_order.insert(Self::rank(self.config.policy, &slot), key.clone());
+        self.expiry_order.insert((now, tick), key.clone());
+        self.entries.insert(key, slot);
+        self.stats.bytes += weight;
+        self.stats.entries += 1;
+        self.stats.insertions += 1;
+        if !hot {
+            self.probation += 1;
+        }
+        previous
     }
 
     /// 데이터를 조회하며 히트/미스를 기록합니다.
     pub fn get(&mut self, key: &K) -> Option<&V> {
-        let result = self.cache.get(key);
-        if result.is_some() {
-            self.stats.hits += 1;
-        } else {
-            self.stats.misses += 1;
+        self.get_if(key, |_| true)
+    }
+
+    /// 키에 해당하는 항목이 `accept`를 만족할 때만 히트로 기록하고 반환합니다.
+    ///
+    /// 키 충돌이나 조건 불일치처럼 항목은 있지만 쓸 수 없는 경우는 미스로 기록됩니다.
+    /// 캐시가 꺼져 있으면 통계를 남기지 않고 None을 반환합니다.
+    pub fn get_if(&mut self, key: &K, accept: impl FnOnce(&V) -> bool) -> Option<&V> {
+        if !self.enabled {
+            return None;
+        }
+        let now = (self.clock)();
+        if self.entries.get(key).is_some_and(|slot| self.is_expired(slot, now)) {
+            self.remove_slot(key);
+            self.stats.expirations += 1;
+        }
+        let tick = self.tick + 1;
+        let policy = self.config.policy;
+        match self.entries.get_mut(key).filter(|slot| accept(&slot.value)) {
+            Some(slot) => {
+                self.eviction_order.remove(&Self::rank(policy, slot));
+                slot.last_used = tick;
+                slot.frequency += 1;
+                // 조회된 A1in 항목은 Am으로 옮겨짐
+                let promoted = !std::mem::replace(&mut slot.hot, true);
+                self.eviction_order.insert(Self::rank(policy, slot), key.clone());
+                self.probation -= usize::from(promoted);
+                self.tick = tick;
+                self.stats.hits += 1;
+                Some(&slot.value)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
         }
-        result
     }
 
+    /// 통계와 사용 순서를 바꾸지 않고, 만료되지 않은 항목을 조회합니다.
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        let now = (self.clock)();
+        self.entries
+            .get(key)
+            .filter(|slot| !self.is_expired(slot, now))
+            .map(|slot| &slot.value)
+    }
+
+    /// 만료되지 않은 항목이 있는지 확인합니다. (`peek`과 같은 기준)
     pub fn contains(&self, key: &K) -> bool {
-        self.cache.contains(key)
+        self.peek(key).is_some()
+    }
+
+    /// 저장된 항목을 오래 사용되지 않은 순서로 반환합니다. (이 순서로 다시 넣으면 사용 순서가 복원됨)
+    pub fn entries_by_recency(&self) -> Vec<(&K, &V)> {
+        let mut slots: Vec<(&K, &Slot<V>)> = self.entries.iter().collect();
+        slots.sort_by_key(|(_, slot)| slot.last_used);
+        slots.into_iter().map(|(key, slot)| (key, &slot.value)).collect()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 캐시를 켜거나 끕니다. 끄면 저장된 항목을 비우며, 통계는 유지됩니다.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.clear();
+        }
     }
 
     /// 현재 히트율을 백분율(%)로 계산하여 반환합니다.
     pub fn hit_rate(&self) -> f64 {
-        let total = self.stats.hits + self.stats.misses;
-        if total == 0 {
-            0.0
-        } else {
-            (self.stats.hits as f64 / total as f64) * 100.0
-        }
+        self.stats.hit_rate()
     }
 
     /// 현재 통계 정보를 반환합니다.
@@ -64,13 +519,45 @@ impl<'a, K: std::hash::Hash + Eq, V> SearchCache<'a, K, V> {
 
     /// 현재 캐시 내부 요소 개수를 반환합니다.
     pub fn len(&self) -> usize {
-        self.cache.len()
+        self.entries.len()
+    }
+
+    /// 캐시가 비어 있는지 확인합니다.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// `keep`이 false를 반환한 항목만 제거하고, 제거된 개수를 반환합니다. 통계는 유지됩니다.
+    pub fn retain(&mut self, mut keep: impl FnMut(&K, &V) -> bool) -> usize {
+        let stale: Vec<K> = self
+            .entries
+            .iter()
+            .filter(|(key, slot)| !keep(key, &slot.value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &stale {
+            self.remove_slot(key);
+        }
+        stale.len()
     }
 
-    /// 캐시를 초기화합니다.
+    /// 저장된 항목을 모두 비웁니다. 누적 통계는 유지됩니다.
     pub fn clear(&mut self) {
-        self.cache.clear();
-        self.stats.hits = 0;
-        self.stats.misses = 0;
+        self.entries.clear();
+        self.eviction_order.clear();
+        self.expiry_order.clear();
+        self.probation = 0;
+        self.forget_ghosts();
+        self.stats.entries = 0;
+        self.stats.bytes = 0;
     }
-}
\ No newline at end of file
+
+    /// 누적 통계(히트, 미스, 저장, 제거, 만료 횟수)만 0으로 되돌립니다.
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats {
+            entries: self.stats.entries,
+            bytes: self.stats.bytes,
+            ..CacheStats::default()
+        };
+    }
+}
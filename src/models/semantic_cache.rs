@@ -0,0 +1,111 @@
+use crate::models::{
+    point::Metric,
+    search_cache::{CachedQuery, CachedSearch},
+};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// 의미 기반 캐시의 적중 통계
+///
+/// `exact_hits`는 정확 일치 캐시에서, `approximate_hits`는 유사 질의로 답한 횟수입니다.
+#[derive(Default, Debug, Clone, PartialEq, Serialize)]
+pub struct SemanticCacheStats {
+    pub exact_hits: u64,
+    pub approximate_hits: u64,
+    pub misses: u64,
+}
+
+/// 거의 같은 질의 벡터에 대해 이전 검색 결과를 재사용하는 근사 캐시
+///
+/// 최근 질의들을 작은 평면 목록으로 보관하고, 조건(ef_search, 필터, 거리 계산 방식)이 같으면서
+/// 질의 벡터의 코사인 유사도가 `threshold` 이상인 항목 중 가장 가까운 것으로 응답합니다.
+#[derive(Debug)]
+pub struct SemanticCache {
+    entries: VecDeque<CachedSearch>,
+    capacity: usize,
+    threshold: f32,
+    stats: SemanticCacheStats,
+}
+
+impl SemanticCache {
+    pub fn new(capacity: usize, threshold: f32) -> Self {
+        SemanticCache {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+            threshold,
+            stats: SemanticCacheStats::default(),
+        }
+    }
+
+    pub fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> &SemanticCacheStats {
+        &self.stats
+    }
+
+    /// 정확 일치 캐시에서 적중한 경우를 기록합니다.
+    pub fn record_exact_hit(&mut self) {
+        self.stats.exact_hits += 1;
+    }
+
+    /// 유사한 질의의 결과로 top_k개를 답할 수 있으면 반환하고, 적중/미스를 기록합니다.
+    ///
+    /// 반환된 거리는 저장된 질의 기준이므로, 호출자가 현재 질의로 다시 계산해야 합니다.
+    pub fn lookup(&mut self, query: &CachedQuery, top_k: usize) -> Option<Vec<(u64, f32)>> {
+        let best = self
+            .entries
+            .iter()
+            .filter(|entry| {
+                entry.query.ef_search == query.ef_search
+                    && entry.query.metric == query.metric
+                    && entry.query.filter == query.filter
+                    && entry.query.vector.len() == query.vector.len()
+            })
+            .filter_map(|entry| {
+                let similarity = 1.0 - Metric::Cosine.distance(&entry.query.vector, &query.vector);
+                let results = entry.answer(top_k)?;
+                (similarity >= self.threshold).then_some((similarity, results))
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+
+        match best {
+            Some((_, results)) => {
+                self.stats.approximate_hits += 1;
+                Some(results)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// 새 검색 결과를 저장합니다. 용량을 넘으면 가장 오래된 항목부터 제거합니다.
+    pub fn insert(&mut self, entry: CachedSearch) {
+        self.entries.retain(|e| !e.query.same_as(&entry.query));
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// 모든 항목을 제거합니다. 통계는 유지됩니다.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// `keep`이 false를 반환한 항목을 제거합니다. 통계는 유지됩니다.
+    pub fn retain(&mut self, keep: impl FnMut(&CachedSearch) -> bool) {
+        self.entries.retain(keep);
+    }
+}
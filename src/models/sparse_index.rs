@@ -0,0 +1,67 @@
+use crate::models::document::SparseVector;
+use std::collections::HashMap;
+
+/// 희소 벡터의 내적 검색을 위한 역색인
+///
+/// 차원 인덱스마다 (문서 ID, 값) 목록을 두어, 질의에 등장하는 차원만 순회합니다.
+#[derive(Default, Debug)]
+pub struct SparseIndex {
+    postings: HashMap<u32, HashMap<u64, f32>>,
+    vectors: HashMap<u64, SparseVector>,
+}
+
+impl SparseIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, id: &u64) -> Option<&SparseVector> {
+        self.vectors.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    /// 문서의 희소 벡터를 색인합니다. 이미 있으면 기존 벡터를 대체합니다.
+    pub fn insert(&mut self, id: u64, vector: SparseVector) {
+        self.remove(&id);
+        for (&index, &value) in vector.indices.iter().zip(&vector.values) {
+            self.postings.entry(index).or_default().insert(id, value);
+        }
+        self.vectors.insert(id, vector);
+    }
+
+    pub fn remove(&mut self, id: &u64) -> Option<SparseVector> {
+        let vector = self.vectors.remove(id)?;
+        for index in &vector.indices {
+            if let Some(posting) = self.postings.get_mut(index) {
+                posting.remove(id);
+                if posting.is_empty() {
+                    self.postings.remove(index);
+                }
+            }
+        }
+        Some(vector)
+    }
+
+    /// 질의와의 내적이 큰 순으로 최대 top_k개의 (ID, 내적)을 반환합니다. 내적이 0인 문서는 제외됩니다.
+    pub fn search(&self, query: &SparseVector, top_k: usize) -> Vec<(u64, f32)> {
+        let mut scores: HashMap<u64, f32> = HashMap::new();
+        for (index, &query_value) in query.indices.iter().zip(&query.values) {
+            if let Some(posting) = self.postings.get(index) {
+                for (&id, &value) in posting {
+                    *scores.entry(id).or_insert(0.0) += query_value * value;
+                }
+            }
+        }
+        let mut results: Vec<(u64, f32)> = scores.into_iter().filter(|(_, score)| *score != 0.0).collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        results.truncate(top_k);
+        results
+    }
+}
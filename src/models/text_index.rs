@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+
+/// 한글, 한자, 가나처럼 띄어쓰기만으로는 단어를 나누기 어려운 문자인지 확인
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{1100}'..='\u{11FF}'   // 한글 자모
+        | '\u{3040}'..='\u{30FF}' // 히라가나, 가타카나
+        | '\u{3130}'..='\u{318F}' // 한글 호환 자모
+        | '\u{4E00}'..='\u{9FFF}' // CJK 통합 한자
+        | '\u{AC00}'..='\u{D7A3}' // 한글 음절
+    )
+}
+
+/// CJK 문자열을 문자 bigram으로 분리 (한 글자짜리는 그대로 사용)
+fn push_bigrams(run: &[char], tokens: &mut Vec<String>) {
+    if run.len() == 1 {
+        tokens.push(run[0].to_string());
+    }
+    for pair in run.windows(2) {
+        tokens.push(pair.iter().collect());
+    }
+}
+
+/// 텍스트를 BM25 색인용 토큰으로 분리합니다.
+///
+/// 영문/숫자는 소문자 단어 단위로, 한글 등 CJK 문자는 조사가 붙어도 매칭되도록
+/// 문자 bigram 단위로 분리합니다. 예: "벡터검색 Engine" -> ["벡터", "터검", "검색", "engine"]
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut cjk_run: Vec<char> = Vec::new();
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            cjk_run.push(c);
+        } else {
+            if !cjk_run.is_empty() {
+                push_bigrams(&cjk_run, &mut tokens);
+                cjk_run.clear();
+            }
+            if c.is_alphanumeric() || c == '_' {
+                word.extend(c.to_lowercase());
+            } else if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    if !cjk_run.is_empty() {
+        push_bigrams(&cjk_run, &mut tokens);
+    }
+    tokens
+}
+
+/// BM25 점수 계산을 위한 역색인
+#[derive(Default, Debug)]
+pub struct TextIndex {
+    /// 토큰 -> (문서 ID -> 토큰 빈도)
+    postings: HashMap<String, HashMap<u64, u32>>,
+    /// 문서 ID -> (토큰 수, 문서에 등장한 고유 토큰 목록)
+    doc_terms: HashMap<u64, (u32, Vec<String>)>,
+    total_length: u64,
+}
+
+impl TextIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 문서의 텍스트를 색인합니다. 이미 색인된 문서라면 기존 내용을 대체합니다.
+    pub fn insert(&mut self, id: u64, text: &str) {
+        self.remove(id);
+        let tokens = tokenize(text);
+        if tokens.is_empty() {
+            return;
+        }
+
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        for token in &tokens {
+            *frequencies.entry(token.clone()).or_insert(0) += 1;
+        }
+        let terms: Vec<String> = frequencies.keys().cloned().collect();
+        for (term, tf) in frequencies {
+            self.postings.entry(term).or_default().insert(id, tf);
+        }
+        self.total_length += tokens.len() as u64;
+        self.doc_terms.insert(id, (tokens.len() as u32, terms));
+    }
+
+    /// 문서를 색인에서 제거합니다.
+    pub fn remove(&mut self, id: u64) {
+        let Some((length, terms)) = self.doc_terms.remove(&id) else {
+            return;
+        };
+        self.total_length -= length as u64;
+        for term in terms {
+            if let Some(posting) = self.postings.get_mut(&term) {
+                posting.remove(&id);
+                if posting.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// 색인된 문서 수를 반환합니다.
+    pub fn len(&self) -> usize {
+        self.doc_terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.doc_terms.is_empty()
+    }
+
+    /// 질의어에 대한 BM25 점수가 높은 순으로 최대 top_k개의 (ID, 점수)를 반환합니다.
+    pub fn search(&self, query: &str, top_k: usize) -> Vec<(u64, f32)> {
+        if self.doc_terms.is_empty() {
+            return Vec::new();
+        }
+        let doc_count = self.doc_terms.len() as f32;
+        let avg_length = self.total_length as f32 / doc_count;
+
+        let mut query_terms = tokenize(query);
+        query_terms.sort();
+        query_terms.dedup();
+
+        let mut scores: HashMap<u64, f32> = HashMap::new();
+        for term in &query_terms {
+            let Some(posting) = self.postings.get(term) else {
+                continue;
+            };
+            let df = posting.len() as f32;
+            let idf = (1.0 + (doc_count - df + 0.5) / (df + 0.5)).ln();
+            for (&id, &tf) in posting {
+                let length = self.doc_terms[&id].0 as f32;
+                let tf = tf as f32;
+                let norm = BM25_K1 * (1.0 - BM25_B + BM25_B * length / avg_length);
+                *scores.entry(id).or_insert(0.0) += idf * tf * (BM25_K1 + 1.0) / (tf + norm);
+            }
+        }
+
+        let mut results: Vec<(u64, f32)> = scores.into_iter().collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        results.truncate(top_k);
+        results
+    }
+}
@@ -0,0 +1,125 @@
+use crate::models::{
+    errors::VectorEngineError,
+    point::{Metric, MetricPoint},
+};
+use instant_distance::{Builder, HnswMap, Search};
+use std::collections::HashMap;
+
+/// 이름이 붙은 임베딩 공간 하나 (제목 임베딩, 본문 임베딩, 새 모델 임베딩 등)
+///
+/// 공간마다 차원, 거리 계산 방식, HNSW 인덱스를 따로 가집니다.
+pub struct VectorSpace {
+    name: String,
+    dimension: usize,
+    metric: Metric,
+    vectors: HashMap<u64, Vec<f32>>,
+    index: HnswMap<MetricPoint, u64>,
+    /// 인덱스에 반영되지 않은 삭제가 있는지 여부
+    dirty: bool,
+}
+
+impl VectorSpace {
+    pub fn new(name: &str, dimension: usize, metric: Metric) -> Self {
+        VectorSpace {
+            name: name.to_string(),
+            dimension,
+            metric,
+            vectors: HashMap::new(),
+            index: Builder::default().build(Vec::<MetricPoint>::new(), Vec::<u64>::new()),
+            dirty: false,
+        }
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    pub fn get(&self, id: &u64) -> Option<&Vec<f32>> {
+        self.vectors.get(id)
+    }
+
+    /// 이 공간에 벡터가 있는 문서 ID들을 반환합니다.
+    pub fn ids(&self) -> impl Iterator<Item = &u64> {
+        self.vectors.keys()
+    }
+
+    /// 이 공간의 차원과 일치하는지 검사
+    pub fn ensure_dimension(&self, vector: &[f32]) -> Result<(), VectorEngineError> {
+        if self.dimension != vector.len() {
+            return Err(VectorEngineError::DimensionMismatch(format!(
+                "입력 벡터의 차원({})이 벡터 공간 '{}'의 차원({})과 일치하지 않습니다.",
+                vector.len(),
+                self.name,
+                self.dimension
+            )));
+        }
+        Ok(())
+    }
+
+    /// 벡터를 추가하거나 교체하고 인덱스를 재구성합니다.
+    pub fn insert(&mut self, id: u64, vector: Vec<f32>) -> Result<(), VectorEngineError> {
+        self.ensure_dimension(&vector)?;
+        self.vectors.insert(id, vector);
+        self.rebuild_index();
+        Ok(())
+    }
+
+    /// 인덱스 재구성 없이 벡터를 적재합니다. (스냅샷 로딩용)
+    pub fn load(&mut self, id: u64, vector: Vec<f32>) -> Result<(), VectorEngineError> {
+        self.ensure_dimension(&vector)?;
+        self.vectors.insert(id, vector);
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// 벡터를 제거합니다. 인덱스는 다음 `rebuild_if_dirty` 호출 때 재구성됩니다.
+    pub fn remove(&mut self, id: &u64) -> Option<Vec<f32>> {
+        let removed = self.vectors.remove(id);
+        if removed.is_some() {
+            self.dirty = true;
+        }
+        removed
+    }
+
+    pub fn rebuild_if_dirty(&mut self) {
+        if self.dirty {
+            self.rebuild_index();
+        }
+    }
+
+    fn rebuild_index(&mut self) {
+        let points = self
+            .vectors
+            .values()
+            .map(|v| MetricPoint { vector: v.clone(), metric: self.metric })
+            .collect();
+        let values = self.vectors.keys().copied().collect();
+        self.index = Builder::default().build(points, values);
+        self.dirty = false;
+    }
+
+    /// 거리 오름차순으로 최대 top_k개의 (ID, 거리)를 반환합니다.
+    pub fn search(&self, query_vector: &[f32], top_k: usize) -> Result<Vec<(u64, f32)>, VectorEngineError> {
+        self.ensure_dimension(query_vector)?;
+        let query_point = MetricPoint { vector: query_vector.to_vec(), metric: self.metric };
+        let mut search = Search::default();
+        Ok(self
+            .index
+            .search(&query_point, &mut search)
+            .take(top_k)
+            .map(|item| (*item.value, item.distance))
+            .collect())
+    }
+}
@@ -0,0 +1,158 @@
+use crate::models::{document::WalRecord, errors::VectorEngineError};
+use prost::Message;
+use serde::Serialize;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// 레코드 앞에 붙는 헤더 크기: 길이(u32 LE) + CRC32(u32 LE)
+const FRAME_HEADER_LEN: usize = 8;
+
+/// CRC-32(IEEE) 조회 테이블
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// 레코드 손상 여부를 검사하기 위한 CRC-32(IEEE) 체크섬
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        CRC32_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// 레코드 하나를 `[길이][CRC32][protobuf 본문]` 형태의 프레임으로 인코딩합니다.
+pub fn encode_frame(record: &WalRecord) -> Vec<u8> {
+    let body = record.encode_to_vec();
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + body.len());
+    frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&crc32(&body).to_le_bytes());
+    frame.extend_from_slice(&body);
+    frame
+}
+
+/// 로그 바이트에서 온전한 레코드들을 순서대로 읽습니다.
+///
+/// 헤더나 본문이 잘렸거나 체크섬이 맞지 않는 첫 프레임에서 멈추며, 그 앞까지의 길이를
+/// 함께 반환합니다. 쓰다가 중단된 꼬리(torn tail)는 이 길이로 잘라내면 됩니다.
+pub fn read_frames(bytes: &[u8]) -> (Vec<WalRecord>, usize) {
+    let mut records = Vec::new();
+    let mut offset: usize = 0;
+    // 길이 계산이 넘치면(32비트 환경의 손상된 길이 등) 잘린 꼬리로 취급
+    while let Some(header) = offset.checked_add(FRAME_HEADER_LEN).and_then(|end| bytes.get(offset..end)) {
+        let len = u32::from_le_bytes(header[0..4].try_into().unwrap()) as usize;
+        let checksum = u32::from_le_bytes(header[4..8].try_into().unwrap());
+        let start = offset + FRAME_HEADER_LEN;
+        let Some(end) = start.checked_add(len) else {
+            break;
+        };
+        let Some(body) = bytes.get(start..end) else {
+            break;
+        };
+        if crc32(body) != checksum {
+            break;
+        }
+        let Ok(record) = WalRecord::decode(body) else {
+            break;
+        };
+        records.push(record);
+        offset = end;
+    }
+    (records, offset)
+}
+
+/// 레코드를 임의의 `Write` sink에 덧붙이는 쓰기 전 로그
+///
+/// 쓰기에 한 번 실패하면 로그 끝이 잘렸을 수 있으므로, 그 뒤의 기록은 재생되지 못합니다.
+/// 이를 막기 위해 실패 이후의 기록은 모두 거부하며, 새 sink를 연결해야 다시 기록할 수 있습니다.
+pub struct WriteAheadLog {
+    sink: Box<dyn Write + Send>,
+    failed: bool,
+}
+
+impl WriteAheadLog {
+    pub fn new(sink: Box<dyn Write + Send>) -> Self {
+        WriteAheadLog { sink, failed: false }
+    }
+
+    /// 레코드 하나를 프레임으로 기록하고 sink를 flush합니다.
+    pub fn append(&mut self, record: &WalRecord) -> Result<(), VectorEngineError> {
+        self.append_all(std::slice::from_ref(record))
+    }
+
+    /// 여러 레코드를 한 번의 쓰기로 기록하고 sink를 flush합니다.
+    pub fn append_all(&mut self, records: &[WalRecord]) -> Result<(), VectorEngineError> {
+        if self.failed {
+            return Err(VectorEngineError::IoError(
+                "이전 쓰기에 실패한 WAL에는 더 기록할 수 없습니다.".to_string(),
+            ));
+        }
+        let bytes: Vec<u8> = records.iter().flat_map(encode_frame).collect();
+        let result = self.sink.write_all(&bytes).and_then(|_| self.sink.flush());
+        self.failed = result.is_err();
+        Ok(result?)
+    }
+
+    pub fn flush(&mut self) -> Result<(), VectorEngineError> {
+        self.sink.flush()?;
+        Ok(())
+    }
+
+    /// 이전 쓰기에 실패하여 더 기록할 수 없는 상태인지 반환합니다.
+    pub fn has_failed(&self) -> bool {
+        self.failed
+    }
+
+    pub fn into_inner(self) -> Box<dyn Write + Send> {
+        self.sink
+    }
+}
+
+/// WAL 재생 결과
+///
+/// `valid_len` 이후의 `truncated_bytes`는 손상되었거나 잘린 꼬리이므로 로그 파일에서 잘라내야 합니다.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WalReplay {
+    /// 적용된 레코드 수
+    pub applied: usize,
+    /// 이미 스냅샷에 반영되어 건너뛴 레코드 수
+    pub skipped: usize,
+    pub valid_len: usize,
+    pub truncated_bytes: usize,
+}
+
+/// 여러 곳에서 함께 쓰는 메모리 버퍼 sink (파일에 직접 쓸 수 없는 WASM 환경에서 사용)
+#[derive(Debug, Clone, Default)]
+pub struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 지금까지 쓰인 바이트를 꺼내고 버퍼를 비웁니다.
+    pub fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
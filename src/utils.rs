@@ -18,4 +18,37 @@ pub fn hash_vector(vec: &[f32]) -> u64 {
         hasher.write_u32(v.to_bits());
     }
     hasher.finish()
-}
\ No newline at end of file
+}
+/// 텍스트 내용의 해시 키를 생성합니다. (임베딩 캐시용)
+///
+/// 스냅샷에 저장되므로 플랫폼과 실행에 관계없이 같은 값을 내는 64비트 FNV-1a를 사용합니다.
+/// 충돌을 막지 못하므로 캐시는 적중 시 원문을 함께 비교합니다.
+#[wasm_bindgen]
+pub fn hash_text(text: &str) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+    text.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = Date, js_name = now)]
+    fn date_now() -> f64;
+}
+
+/// 현재 시각을 Unix epoch 기준 밀리초로 반환합니다.
+///
+/// wasm32에서는 `std::time`을 쓸 수 없으므로 JS의 `Date.now()`를 사용합니다.
+pub fn now_millis() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        date_now() as u64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64)
+    }
+}
@@ -1,26 +1,95 @@
 // src/wasm_api.rs
 use wasm_bindgen::prelude::*;
-use crate::models::VectorEngine;
+use serde::{Serialize, de::DeserializeOwned};
+use crate::models::{CacheConfig, CacheStats, Database, Filter, FusionMethod, GroupAggregate, Metric, PathScope, Payload, RecommendStrategy, SharedBuffer, SparseVector, VectorEngine, VectorEngineError};
+
+/// JS 값을 Rust 타입으로 변환하고, 실패하면 DeserializationError를 JS 값으로 반환
+fn from_js<T: DeserializeOwned>(value: JsValue) -> Result<T, JsValue> {
+    serde_wasm_bindgen::from_value(value)
+        .map_err(|e| serde_wasm_bindgen::to_value(&VectorEngineError::DeserializationError(e.to_string())).unwrap())
+}
+
+/// 맵을 JS Map이 아닌 일반 객체로 내보내기 위해 JSON 호환 직렬화를 사용
+fn to_js_json<T: Serialize>(value: &T) -> JsValue {
+    value.serialize(&serde_wasm_bindgen::Serializer::json_compatible()).unwrap()
+}
+
+/// 여러 벡터를 이어 붙인 평탄화된 배열을 `dimension` 크기의 벡터들로 나눔
+fn split_flat(values: &[f32], dimension: usize) -> Result<Vec<Vec<f32>>, JsValue> {
+    if dimension == 0 || !values.len().is_multiple_of(dimension) {
+        let error = VectorEngineError::DimensionMismatch(format!(
+            "입력 배열의 길이({})가 엔진의 차원({})의 배수가 아닙니다.",
+            values.len(),
+            dimension
+        ));
+        return Err(serde_wasm_bindgen::to_value(&error).unwrap());
+    }
+    Ok(values.chunks(dimension).map(<[f32]>::to_vec).collect())
+}
+
+/// 인덱스/값 배열로 희소 벡터를 만들고, 길이가 다르면 DimensionMismatch를 JS 값으로 반환
+fn to_sparse(indices: &[u32], values: &[f32]) -> Result<SparseVector, JsValue> {
+    SparseVector::new(indices.to_vec(), values.to_vec()).ok_or_else(|| {
+        let error = VectorEngineError::DimensionMismatch(format!(
+            "희소 벡터의 인덱스 수({})와 값의 수({})가 일치하지 않습니다.",
+            indices.len(),
+            values.len()
+        ));
+        serde_wasm_bindgen::to_value(&error).unwrap()
+    })
+}
+
+/// JS에 노출되는 쿼리 캐시 통계 (u64 값은 JS에서 BigInt로 전달됩니다)
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct WasmCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// 백분율(%)
+    pub hit_rate: f64,
+    pub insertions: u64,
+    pub evictions: u64,
+    pub expirations: u64,
+    pub entries: usize,
+    pub bytes: usize,
+}
+
+impl From<&CacheStats> for WasmCacheStats {
+    fn from(stats: &CacheStats) -> Self {
+        WasmCacheStats {
+            hits: stats.hits,
+            misses: stats.misses,
+            hit_rate: stats.hit_rate(),
+            insertions: stats.insertions,
+            evictions: stats.evictions,
+            expirations: stats.expirations,
+            entries: stats.entries,
+            bytes: stats.bytes,
+        }
+    }
+}
 
 #[wasm_bindgen]
 pub struct WasmVectorEngine {
     engine: VectorEngine,
+    /// 연결된 WAL이 기록되는 메모리 버퍼 (JS가 주기적으로 꺼내 저장)
+    wal_buffer: Option<SharedBuffer>,
 }
 
 #[wasm_bindgen]
 impl WasmVectorEngine {
     #[wasm_bindgen(constructor)]
     pub fn new(dimension: usize) -> Self {
-        Self { engine: VectorEngine::new(dimension) }
+        Self { engine: VectorEngine::new(dimension), wal_buffer: None }
     }
 
     pub fn load_from_bytes(bytes: &[u8], dimension: usize) -> Result<WasmVectorEngine, JsValue> {
         let engine = VectorEngine::load_from_bytes(bytes, dimension)
             .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
-        Ok(Self { engine })
+        Ok(Self { engine, wal_buffer: None })
     }
 
-    pub fn save_to_bytes(&self) -> Result<Vec<u8>, JsValue> {
+    pub fn save_to_bytes(&mut self) -> Result<Vec<u8>, JsValue> {
         self.engine.save_to_bytes().map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
     }
 
@@ -36,12 +105,702 @@ impl WasmVectorEngine {
         self.engine.delete_document(&id).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
     }
 
+    pub fn add_document_strict(&mut self, id: u64, vector: &[f32]) -> Result<(), JsValue> {
+        self.engine.add_document_strict(id, vector.to_vec()).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 결과로 "Inserted" 또는 "Updated" 문자열을 반환합니다.
+    pub fn upsert(&mut self, id: u64, vector: &[f32]) -> Result<JsValue, JsValue> {
+        let outcome = self.engine.upsert(id, vector.to_vec())
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&outcome).unwrap())
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.engine.contains(&id)
+    }
+
+    /// `{ id, vector, payload, path, key, group }` 객체를 반환하며, 문서가 없으면 undefined를 반환합니다.
+    pub fn get(&self, id: u64) -> JsValue {
+        match self.engine.get(&id) {
+            Some(record) => to_js_json(&record),
+            None => JsValue::UNDEFINED,
+        }
+    }
+
+    /// `{ documents, next_cursor }`를 반환합니다. `filter`는 생략할 수 있습니다.
+    pub fn scroll(&self, cursor: Option<u64>, limit: usize, filter: JsValue) -> Result<JsValue, JsValue> {
+        let filter: Option<Filter> = if filter.is_undefined() || filter.is_null() {
+            None
+        } else {
+            Some(from_js(filter)?)
+        };
+        Ok(to_js_json(&self.engine.scroll(cursor, limit, filter.as_ref())))
+    }
+
+    pub fn payload(&self, id: u64) -> JsValue {
+        match self.engine.payload(&id) {
+            Some(payload) => to_js_json(payload),
+            None => JsValue::UNDEFINED,
+        }
+    }
+
+    pub fn set_payload(&mut self, id: u64, payload: JsValue) -> Result<(), JsValue> {
+        let payload: Payload = from_js(payload)?;
+        self.engine.set_payload(&id, payload).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn patch_payload(&mut self, id: u64, patch: JsValue) -> Result<(), JsValue> {
+        let patch: Payload = from_js(patch)?;
+        self.engine.patch_payload(&id, &patch).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 필터를 만족하는 문서를 모두 삭제하고 삭제된 개수를 반환합니다.
+    pub fn delete_where(&mut self, filter: JsValue) -> Result<usize, JsValue> {
+        let filter: Filter = from_js(filter)?;
+        self.engine.delete_where(&filter).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 필터를 만족하는 문서의 메타데이터에 patch를 병합하고 변경된 개수를 반환합니다.
+    pub fn update_payload_where(&mut self, filter: JsValue, patch: JsValue) -> Result<usize, JsValue> {
+        let filter: Filter = from_js(filter)?;
+        let patch: Payload = from_js(patch)?;
+        self.engine.update_payload_where(&filter, &patch).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
     pub fn search(&mut self, query_vector: &[f32], top_k: usize) -> Result<JsValue, JsValue> {
-        let results = self.engine.search(&query_vector.to_vec(), top_k)
+        let results = self.engine.search(query_vector, top_k)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    /// `filter`는 Filter JSON이며, 결과는 쿼리 캐시에 저장됩니다.
+    pub fn search_filtered(&mut self, query_vector: &[f32], top_k: usize, filter: JsValue) -> Result<JsValue, JsValue> {
+        let filter: Option<Filter> = from_js(filter)?;
+        let results = self.engine.search_filtered(query_vector, top_k, filter.as_ref())
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    pub fn set_ef_search(&mut self, ef_search: usize) -> Result<(), JsValue> {
+        self.engine.set_ef_search(ef_search).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// `config`는 `{ policy: "Lru" | "Lfu" | "TwoQueue", max_entries, max_bytes, ttl_ms }` 형태이며 생략한 값은 기본값을 사용합니다.
+    pub fn configure_query_cache(&mut self, config: JsValue) -> Result<(), JsValue> {
+        let config: CacheConfig = from_js(config)?;
+        self.engine.configure_query_cache(config);
+        Ok(())
+    }
+
+    pub fn query_cache_stats(&self) -> WasmCacheStats {
+        WasmCacheStats::from(self.engine.query_cache_stats())
+    }
+
+    pub fn reset_query_cache_stats(&mut self) {
+        self.engine.reset_query_cache_stats();
+    }
+
+    pub fn resize_query_cache(&mut self, max_entries: usize) {
+        self.engine.resize_query_cache(max_entries);
+    }
+
+    pub fn set_query_cache_enabled(&mut self, enabled: bool) {
+        self.engine.set_query_cache_enabled(enabled);
+    }
+
+    pub fn clear_query_cache(&mut self) {
+        self.engine.clear_query_cache();
+    }
+
+    /// 평탄화된 질의 벡터 배열로 캐시를 미리 채우고, 새로 저장된 항목 수를 반환합니다.
+    pub fn warm_query_cache(&mut self, queries: &[f32], top_k: usize) -> Result<usize, JsValue> {
+        let queries = split_flat(queries, self.engine.dimension())?;
+        self.engine.warm_query_cache(&queries, top_k).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 임베딩 캐시에 없는 텍스트를 중복 없이 반환합니다. JS는 이 텍스트만 임베딩하면 됩니다.
+    pub fn uncached_texts(&self, texts: Vec<String>) -> Vec<String> {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        self.engine.uncached_texts(&texts).into_iter().map(str::to_string).collect()
+    }
+
+    /// 텍스트들과 평탄화된 임베딩 배열을 받아 임베딩 캐시에 저장합니다.
+    pub fn cache_embeddings(&mut self, texts: Vec<String>, vectors: &[f32]) -> Result<(), JsValue> {
+        let vectors = split_flat(vectors, self.engine.dimension())?;
+        if vectors.len() != texts.len() {
+            let error = VectorEngineError::DimensionMismatch(format!(
+                "임베딩 수({})가 텍스트 수({})와 일치하지 않습니다.",
+                vectors.len(),
+                texts.len()
+            ));
+            return Err(serde_wasm_bindgen::to_value(&error).unwrap());
+        }
+        for (text, vector) in texts.iter().zip(vectors) {
+            self.engine.cache_embedding(text, vector).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        }
+        Ok(())
+    }
+
+    /// 텍스트 문서들을 추가하고 `{ cache_hits, embedded }`를 반환합니다.
+    ///
+    /// `vectors`는 같은 `texts`로 `uncached_texts`를 호출해 받은 텍스트들의 임베딩을 그 순서대로
+    /// 이어 붙인 평탄화된 배열입니다. 캐시에 있는 텍스트는 적중으로, `vectors`로 받은 텍스트는
+    /// 새로 임베딩한 것으로 집계되며, 수가 맞지 않으면 InvalidInput을 반환합니다.
+    pub fn add_text_documents(&mut self, ids: &[u64], texts: Vec<String>, vectors: &[f32]) -> Result<JsValue, JsValue> {
+        if ids.len() != texts.len() {
+            let error = VectorEngineError::DimensionMismatch(format!(
+                "ID 수({})와 텍스트 수({})가 일치하지 않습니다.",
+                ids.len(),
+                texts.len()
+            ));
+            return Err(serde_wasm_bindgen::to_value(&error).unwrap());
+        }
+        let vectors = split_flat(vectors, self.engine.dimension())?;
+        let documents: Vec<(u64, &str)> = ids.iter().copied().zip(texts.iter().map(String::as_str)).collect();
+        let texts: Vec<&str> = documents.iter().map(|(_, text)| *text).collect();
+        let missing = self.engine.uncached_texts(&texts).len();
+        if missing != vectors.len() {
+            let error = VectorEngineError::InvalidInput(format!(
+                "임베딩 수({})가 캐시에 없는 텍스트 수({})와 일치하지 않습니다.",
+                vectors.len(),
+                missing
+            ));
+            return Err(serde_wasm_bindgen::to_value(&error).unwrap());
+        }
+        let report = self.engine
+            .add_text_documents(&documents, |_| Ok(vectors))
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&report).unwrap())
+    }
+
+    pub fn embedding_cache_stats(&self) -> WasmCacheStats {
+        WasmCacheStats::from(self.engine.embedding_cache_stats())
+    }
+
+    /// `threshold`는 적중으로 볼 질의 벡터 간 코사인 유사도의 최솟값입니다. (예: 0.98)
+    pub fn enable_semantic_cache(&mut self, capacity: usize, threshold: f32) {
+        self.engine.enable_semantic_cache(capacity, threshold);
+    }
+
+    pub fn disable_semantic_cache(&mut self) {
+        self.engine.disable_semantic_cache();
+    }
+
+    /// `{ exact_hits, approximate_hits, misses }` 또는 캐시가 꺼져 있으면 null을 반환합니다.
+    pub fn semantic_cache_stats(&self) -> JsValue {
+        match self.engine.semantic_cache_stats() {
+            Some(stats) => serde_wasm_bindgen::to_value(stats).unwrap(),
+            None => JsValue::NULL,
+        }
+    }
+
+    /// 마지막 스냅샷 이후 바뀐 문서만 담은 델타 스냅샷을 반환합니다. 저장한 뒤 `commit_delta`를 호출해야 합니다.
+    pub fn save_delta(&mut self) -> Result<Vec<u8>, JsValue> {
+        self.engine.save_delta().map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 저장을 마친 델타의 세대 번호(`snapshot_generation() + 1`)로 델타를 확정합니다.
+    pub fn commit_delta(&mut self, generation: u64) -> Result<(), JsValue> {
+        self.engine.commit_delta(generation).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn snapshot_generation(&self) -> u64 {
+        self.engine.snapshot_generation()
+    }
+
+    /// 델타 스냅샷을 저장된 순서대로 하나씩 적용합니다.
+    pub fn apply_delta(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        self.engine.apply_delta(bytes).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 전체 스냅샷을 만들고 변경 추적을 초기화합니다. 이전 기준 스냅샷과 델타들은 버려도 됩니다.
+    pub fn compact(&mut self) -> Result<Vec<u8>, JsValue> {
+        self.engine.compact().map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.engine.has_unsaved_changes()
+    }
+
+    /// 스냅샷과 그 이후의 WAL 바이트로 엔진을 복구합니다.
+    pub fn load_with_wal(snapshot: &[u8], log: &[u8], dimension: usize) -> Result<WasmVectorEngine, JsValue> {
+        let (engine, _) = VectorEngine::load_with_wal(snapshot, log, dimension)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(Self { engine, wal_buffer: None })
+    }
+
+    /// 이후의 변경을 메모리 버퍼에 WAL로 기록합니다. 기록된 바이트는 `take_wal_bytes`로 꺼냅니다.
+    ///
+    /// 이미 버퍼가 연결되어 있으면 아직 꺼내지 않은 바이트를 잃지 않도록 같은 버퍼를 이어서 씁니다.
+    pub fn attach_wal_buffer(&mut self) -> Result<(), JsValue> {
+        let buffer = self.wal_buffer.clone().unwrap_or_default();
+        self.engine.attach_wal(buffer.clone()).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        self.wal_buffer = Some(buffer);
+        Ok(())
+    }
+
+    pub fn detach_wal(&mut self) {
+        self.engine.detach_wal();
+        self.wal_buffer = None;
+    }
+
+    /// 마지막으로 꺼낸 이후 새로 쌓인 WAL 바이트를 반환합니다. JS는 이를 로그 뒤에 덧붙여 저장합니다.
+    pub fn take_wal_bytes(&self) -> Vec<u8> {
+        self.wal_buffer.as_ref().map(SharedBuffer::take).unwrap_or_default()
+    }
+
+    pub fn wal_sequence(&self) -> u64 {
+        self.engine.wal_sequence()
+    }
+
+    /// 스냅샷을 만들고 WAL 버퍼를 새로 시작합니다. 스냅샷을 저장한 뒤 이전 로그는 버려도 됩니다.
+    pub fn checkpoint(&mut self) -> Result<Vec<u8>, JsValue> {
+        let buffer = SharedBuffer::new();
+        let snapshot = self
+            .engine
+            .checkpoint(buffer.clone())
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        self.wal_buffer = Some(buffer);
+        Ok(snapshot)
+    }
+
+    /// WAL 바이트를 재생하고 `{ applied, skipped, valid_len, truncated_bytes }`를 반환합니다.
+    pub fn replay_wal(&mut self, log: &[u8]) -> Result<JsValue, JsValue> {
+        let replay = self.engine.replay_wal(log).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&replay).unwrap())
+    }
+
+    /// ID를 자동 할당하여 문서를 추가하고, 할당된 ID를 반환합니다.
+    pub fn insert(&mut self, vector: &[f32]) -> Result<u64, JsValue> {
+        self.engine.insert(vector.to_vec()).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// `vectors`는 `dimension` 크기의 벡터들을 이어 붙인 평탄화된 배열입니다.
+    pub fn insert_batch(&mut self, vectors: &[f32]) -> Result<Vec<u64>, JsValue> {
+        let vectors = split_flat(vectors, self.engine.dimension())?;
+        self.engine.insert_batch(vectors).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 문자열 ID로 문서를 추가합니다. 내부 ID는 엔진이 할당합니다.
+    pub fn add_document_by_key(&mut self, key: &str, vector: &[f32]) -> Result<(), JsValue> {
+        self.engine.add_document_by_key(key, vector.to_vec()).map(|_| ()).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn update_document_by_key(&mut self, key: &str, vector: &[f32]) -> Result<(), JsValue> {
+        self.engine.update_document_by_key(key, vector.to_vec()).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn delete_document_by_key(&mut self, key: &str) -> Result<(), JsValue> {
+        self.engine.delete_document_by_key(key).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn rename_document(&mut self, old_key: &str, new_key: &str) -> Result<(), JsValue> {
+        self.engine.rename_document(old_key, new_key).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// `new_key`를 쓰던 문서가 있으면 삭제하고 이름을 바꿉니다.
+    pub fn rename_document_replacing(&mut self, old_key: &str, new_key: &str) -> Result<(), JsValue> {
+        self.engine.rename_document_replacing(old_key, new_key).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 결과를 `[key, distance][]` 형태로 반환합니다.
+    pub fn search_keys(&self, query_vector: &[f32], top_k: usize) -> Result<JsValue, JsValue> {
+        let results = self.engine.search_keys(query_vector, top_k)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    pub fn add_document_with_path(&mut self, id: u64, vector: &[f32], path: &str) -> Result<(), JsValue> {
+        self.engine.add_document_with_path(id, vector.to_vec(), path).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn set_document_path(&mut self, id: u64, path: &str) -> Result<(), JsValue> {
+        self.engine.set_document_path(&id, path).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn document_path(&self, id: u64) -> Option<String> {
+        self.engine.document_path(&id).map(str::to_string)
+    }
+
+    /// `scope`는 `{ include: string[], exclude: string[] }` 형태의 객체입니다.
+    pub fn search_scoped(&self, query_vector: &[f32], top_k: usize, scope: JsValue) -> Result<JsValue, JsValue> {
+        let scope: PathScope = from_js(scope)?;
+        let results = self.engine.search_scoped(query_vector, top_k, &scope)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    pub fn add_document_with_text(&mut self, id: u64, vector: &[f32], text: &str) -> Result<(), JsValue> {
+        self.engine.add_document_with_text(id, vector.to_vec(), text).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn set_document_text(&mut self, id: u64, text: &str) -> Result<(), JsValue> {
+        self.engine.set_document_text(&id, text).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 결과를 `[id, bm25_score][]` 형태로 반환합니다.
+    pub fn keyword_search(&self, text: &str, top_k: usize) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.engine.keyword_search(text, top_k)).unwrap()
+    }
+
+    /// 결과를 `[id, fused_score][]` 형태로 반환합니다. 점수가 높을수록 관련도가 높습니다.
+    pub fn hybrid_search(&self, text: &str, query_vector: &[f32], top_k: usize, alpha: f32) -> Result<JsValue, JsValue> {
+        let results = self.engine.hybrid_search(text, query_vector, top_k, alpha)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    /// MMR로 다양성을 고려한 검색 결과를 `[id, distance][]` 형태로 반환합니다.
+    pub fn search_mmr(&self, query_vector: &[f32], top_k: usize, fetch_k: usize, lambda: f32) -> Result<JsValue, JsValue> {
+        let results = self.engine.search_mmr(query_vector, top_k, fetch_k, lambda)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    /// 저장된 문서와 비슷한 문서를 `[id, distance][]` 형태로 반환합니다. 원본 문서는 제외됩니다.
+    pub fn search_by_id(&self, id: u64, top_k: usize, group_key: Option<String>) -> Result<JsValue, JsValue> {
+        let results = self.engine.search_by_id(&id, top_k, group_key.as_deref())
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    /// `strategy`는 "AverageVector" 또는 "BestScore"이며, 결과는 `[id, distance][]` 형태입니다.
+    pub fn recommend(&self, positive_ids: &[u64], negative_ids: &[u64], top_k: usize, strategy: JsValue) -> Result<JsValue, JsValue> {
+        let strategy: RecommendStrategy = from_js(strategy)?;
+        let results = self.engine.recommend(positive_ids, negative_ids, top_k, strategy)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    /// `queries`는 질의 벡터들을 이어 붙인 평탄화된 배열이며, `fusion`은
+    /// "ReciprocalRank", "MaxScore", "SumScore" 중 하나입니다. 결과는 `[id, score][]` 형태입니다.
+    pub fn search_multi(&self, queries: &[f32], top_k: usize, fusion: JsValue) -> Result<JsValue, JsValue> {
+        let fusion: FusionMethod = from_js(fusion)?;
+        let queries = split_flat(queries, self.engine.dimension())?;
+        let results = self.engine.search_multi(&queries, top_k, fusion)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    /// 한 노트의 청크들을 추가하고 할당된 ID들을 반환합니다. `vectors`는 평탄화된 배열입니다.
+    pub fn add_chunks(&mut self, group: &str, vectors: &[f32]) -> Result<Vec<u64>, JsValue> {
+        let vectors = split_flat(vectors, self.engine.dimension())?;
+        self.engine.add_chunks(group, vectors).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn set_document_group(&mut self, id: u64, group: &str) -> Result<(), JsValue> {
+        self.engine.set_document_group(&id, group).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 그룹에 속한 모든 청크를 삭제하고 삭제된 개수를 반환합니다.
+    pub fn delete_group(&mut self, group: &str) -> Result<usize, JsValue> {
+        self.engine.delete_group(group).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// `aggregate`는 "Max" 또는 "Mean"이며, 결과는 `{ group, score, chunks }[]` 형태입니다.
+    pub fn search_groups(&self, query_vector: &[f32], group_count: usize, chunks_per_group: usize, aggregate: JsValue) -> Result<JsValue, JsValue> {
+        let aggregate: GroupAggregate = from_js(aggregate)?;
+        let hits = self.engine.search_groups(query_vector, group_count, chunks_per_group, aggregate)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&hits).unwrap())
+    }
+
+    /// 토큰마다 임베딩을 가지는 문서를 추가합니다. `tokens`는 토큰 벡터들을 이어 붙인 평탄화된 배열입니다.
+    pub fn add_token_document(&mut self, id: u64, tokens: &[f32]) -> Result<(), JsValue> {
+        let tokens = split_flat(tokens, self.engine.dimension())?;
+        self.engine.add_token_document(id, tokens).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn delete_token_document(&mut self, id: u64) -> Result<(), JsValue> {
+        self.engine.delete_token_document(&id).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// MaxSim 검색 결과를 `[id, score][]` 형태로 반환합니다. `query_tokens`는 평탄화된 배열입니다.
+    pub fn search_maxsim(&self, query_tokens: &[f32], top_k: usize, candidates_per_token: usize) -> Result<JsValue, JsValue> {
+        let query_tokens = split_flat(query_tokens, self.engine.dimension())?;
+        let results = self.engine.search_maxsim(&query_tokens, top_k, candidates_per_token)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    pub fn token_document_count(&self) -> usize { self.engine.token_document_count() }
+
+    pub fn set_sparse_vector(&mut self, id: u64, indices: &[u32], values: &[f32]) -> Result<(), JsValue> {
+        let sparse = to_sparse(indices, values)?;
+        self.engine.set_sparse_vector(&id, sparse).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 희소 벡터 내적 검색 결과를 `[id, score][]` 형태로 반환합니다.
+    pub fn sparse_search(&self, indices: &[u32], values: &[f32], top_k: usize) -> Result<JsValue, JsValue> {
+        let query = to_sparse(indices, values)?;
+        Ok(serde_wasm_bindgen::to_value(&self.engine.sparse_search(&query, top_k)).unwrap())
+    }
+
+    /// 희소+밀집 결합 검색 결과를 `[id, score][]` 형태로 반환합니다.
+    pub fn hybrid_sparse_search(&self, dense_query: &[f32], indices: &[u32], values: &[f32], top_k: usize, alpha: f32) -> Result<JsValue, JsValue> {
+        let sparse_query = to_sparse(indices, values)?;
+        let results = self.engine.hybrid_sparse_search(dense_query, &sparse_query, top_k, alpha)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    /// `metric`은 "Cosine", "Euclidean", "DotProduct" 중 하나입니다.
+    pub fn create_vector_space(&mut self, name: &str, dimension: usize, metric: JsValue) -> Result<(), JsValue> {
+        let metric: Metric = from_js(metric)?;
+        self.engine.create_vector_space(name, dimension, metric).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn drop_vector_space(&mut self, name: &str) -> Result<(), JsValue> {
+        self.engine.drop_vector_space(name).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn vector_space_names(&self) -> Vec<String> {
+        self.engine.vector_space_names()
+    }
+
+    pub fn set_named_vector(&mut self, id: u64, space: &str, vector: &[f32]) -> Result<(), JsValue> {
+        self.engine.set_named_vector(&id, space, vector.to_vec()).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 이름 있는 벡터 공간의 검색 결과를 `[id, distance][]` 형태로 반환합니다.
+    pub fn search_space(&self, space: &str, query_vector: &[f32], top_k: usize) -> Result<JsValue, JsValue> {
+        let results = self.engine.search_space(space, query_vector, top_k)
             .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
         Ok(serde_wasm_bindgen::to_value(&results).unwrap())
     }
 
     pub fn document_count(&self) -> usize { self.engine.document_count() }
     pub fn dimension(&self) -> usize { self.engine.dimension() }
-}
\ No newline at end of file
+}
+
+/// 여러 컬렉션을 하나의 스냅샷으로 관리하는 WASM 래퍼
+#[wasm_bindgen]
+pub struct WasmDatabase {
+    database: Database,
+}
+
+impl Default for WasmDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WasmDatabase {
+    /// 컬렉션을 찾아 `f`를 실행하고, 오류는 JS 값으로 변환
+    fn with_collection<T>(
+        &self,
+        name: &str,
+        f: impl FnOnce(&VectorEngine) -> Result<T, VectorEngineError>,
+    ) -> Result<T, JsValue> {
+        self.database.collection(name).and_then(f).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    fn with_collection_mut<T>(
+        &mut self,
+        name: &str,
+        f: impl FnOnce(&mut VectorEngine) -> Result<T, VectorEngineError>,
+    ) -> Result<T, JsValue> {
+        self.database.collection_mut(name).and_then(f).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+}
+
+#[wasm_bindgen]
+impl WasmDatabase {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self { database: Database::new() }
+    }
+
+    pub fn load_from_bytes(bytes: &[u8]) -> Result<WasmDatabase, JsValue> {
+        let database = Database::load_from_bytes(bytes)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(Self { database })
+    }
+
+    pub fn save_to_bytes(&self) -> Result<Vec<u8>, JsValue> {
+        self.database.save_to_bytes().map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// `metric`은 "Cosine", "Euclidean", "DotProduct" 중 하나입니다.
+    pub fn create_collection(&mut self, name: &str, dimension: usize, metric: JsValue) -> Result<(), JsValue> {
+        let metric: Metric = from_js(metric)?;
+        self.database.create_collection(name, dimension, metric)
+            .map(|_| ())
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn drop_collection(&mut self, name: &str) -> Result<(), JsValue> {
+        self.database.drop_collection(name).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn rename_collection(&mut self, old_name: &str, new_name: &str) -> Result<(), JsValue> {
+        self.database.rename_collection(old_name, new_name).map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn list_collections(&self) -> Vec<String> {
+        self.database.list_collections()
+    }
+
+    pub fn add_document(&mut self, collection: &str, id: u64, vector: &[f32]) -> Result<(), JsValue> {
+        self.database.collection_mut(collection)
+            .and_then(|engine| engine.add_document(id, vector.to_vec()))
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn update_document(&mut self, collection: &str, id: u64, vector: &[f32]) -> Result<(), JsValue> {
+        self.database.collection_mut(collection)
+            .and_then(|engine| engine.update_document(&id, vector.to_vec()))
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn delete_document(&mut self, collection: &str, id: u64) -> Result<(), JsValue> {
+        self.database.collection_mut(collection)
+            .and_then(|engine| engine.delete_document(&id))
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn search(&mut self, collection: &str, query_vector: &[f32], top_k: usize) -> Result<JsValue, JsValue> {
+        let results = self.database.collection_mut(collection)
+            .and_then(|engine| engine.search(query_vector, top_k))
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    /// 여러 컬렉션을 한 번에 검색하여 `{ collection, id, score }[]` 형태로 반환합니다.
+    pub fn federated_search(&self, collections: Vec<String>, query_vector: &[f32], top_k: usize) -> Result<JsValue, JsValue> {
+        let hits = self.database.federated_search(&collections, query_vector, top_k)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())?;
+        Ok(serde_wasm_bindgen::to_value(&hits).unwrap())
+    }
+
+    pub fn document_count(&self, collection: &str) -> Result<usize, JsValue> {
+        self.database.collection(collection)
+            .map(VectorEngine::document_count)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    pub fn dimension(&self, collection: &str) -> Result<usize, JsValue> {
+        self.database.collection(collection)
+            .map(VectorEngine::dimension)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap())
+    }
+
+    /// 결과로 "Inserted" 또는 "Updated" 문자열을 반환합니다.
+    pub fn upsert(&mut self, collection: &str, id: u64, vector: &[f32]) -> Result<JsValue, JsValue> {
+        let outcome = self.with_collection_mut(collection, |engine| engine.upsert(id, vector.to_vec()))?;
+        Ok(serde_wasm_bindgen::to_value(&outcome).unwrap())
+    }
+
+    /// ID를 자동 할당하여 문서를 추가하고, 할당된 ID를 반환합니다.
+    pub fn insert(&mut self, collection: &str, vector: &[f32]) -> Result<u64, JsValue> {
+        self.with_collection_mut(collection, |engine| engine.insert(vector.to_vec()))
+    }
+
+    /// `vectors`는 컬렉션 차원 크기의 벡터들을 이어 붙인 평탄화된 배열입니다.
+    pub fn insert_batch(&mut self, collection: &str, vectors: &[f32]) -> Result<Vec<u64>, JsValue> {
+        let vectors = split_flat(vectors, self.dimension(collection)?)?;
+        self.with_collection_mut(collection, |engine| engine.insert_batch(vectors))
+    }
+
+    /// 문자열 ID로 문서를 추가하고 할당된 내부 ID를 반환합니다.
+    pub fn add_document_by_key(&mut self, collection: &str, key: &str, vector: &[f32]) -> Result<u64, JsValue> {
+        self.with_collection_mut(collection, |engine| engine.add_document_by_key(key, vector.to_vec()))
+    }
+
+    pub fn delete_document_by_key(&mut self, collection: &str, key: &str) -> Result<(), JsValue> {
+        self.with_collection_mut(collection, |engine| engine.delete_document_by_key(key))
+    }
+
+    pub fn add_document_with_path(&mut self, collection: &str, id: u64, vector: &[f32], path: &str) -> Result<(), JsValue> {
+        self.with_collection_mut(collection, |engine| engine.add_document_with_path(id, vector.to_vec(), path))
+    }
+
+    pub fn set_document_path(&mut self, collection: &str, id: u64, path: &str) -> Result<(), JsValue> {
+        self.with_collection_mut(collection, |engine| engine.set_document_path(&id, path))
+    }
+
+    pub fn contains(&self, collection: &str, id: u64) -> Result<bool, JsValue> {
+        self.with_collection(collection, |engine| Ok(engine.contains(&id)))
+    }
+
+    /// `{ id, vector, payload, path, key, group }` 객체를 반환하며, 문서가 없으면 undefined를 반환합니다.
+    pub fn get(&self, collection: &str, id: u64) -> Result<JsValue, JsValue> {
+        let record = self.with_collection(collection, |engine| Ok(engine.get(&id)))?;
+        Ok(record.map_or(JsValue::UNDEFINED, |record| to_js_json(&record)))
+    }
+
+    /// `{ documents, next_cursor }`를 반환합니다. `filter`는 생략할 수 있습니다.
+    pub fn scroll(&self, collection: &str, cursor: Option<u64>, limit: usize, filter: JsValue) -> Result<JsValue, JsValue> {
+        let filter: Option<Filter> = if filter.is_undefined() || filter.is_null() {
+            None
+        } else {
+            Some(from_js(filter)?)
+        };
+        let page = self.with_collection(collection, |engine| Ok(engine.scroll(cursor, limit, filter.as_ref())))?;
+        Ok(to_js_json(&page))
+    }
+
+    pub fn payload(&self, collection: &str, id: u64) -> Result<JsValue, JsValue> {
+        self.with_collection(collection, |engine| {
+            Ok(engine.payload(&id).map_or(JsValue::UNDEFINED, to_js_json))
+        })
+    }
+
+    pub fn set_payload(&mut self, collection: &str, id: u64, payload: JsValue) -> Result<(), JsValue> {
+        let payload: Payload = from_js(payload)?;
+        self.with_collection_mut(collection, |engine| engine.set_payload(&id, payload))
+    }
+
+    pub fn patch_payload(&mut self, collection: &str, id: u64, patch: JsValue) -> Result<(), JsValue> {
+        let patch: Payload = from_js(patch)?;
+        self.with_collection_mut(collection, |engine| engine.patch_payload(&id, &patch))
+    }
+
+    /// 필터를 만족하는 문서를 모두 삭제하고 삭제된 개수를 반환합니다.
+    pub fn delete_where(&mut self, collection: &str, filter: JsValue) -> Result<usize, JsValue> {
+        let filter: Filter = from_js(filter)?;
+        self.with_collection_mut(collection, |engine| engine.delete_where(&filter))
+    }
+
+    /// 필터를 만족하는 문서의 메타데이터에 patch를 병합하고 변경된 개수를 반환합니다.
+    pub fn update_payload_where(&mut self, collection: &str, filter: JsValue, patch: JsValue) -> Result<usize, JsValue> {
+        let filter: Filter = from_js(filter)?;
+        let patch: Payload = from_js(patch)?;
+        self.with_collection_mut(collection, |engine| engine.update_payload_where(&filter, &patch))
+    }
+
+    /// `filter`는 Filter JSON이며, 결과는 컬렉션의 쿼리 캐시에 저장됩니다.
+    pub fn search_filtered(&mut self, collection: &str, query_vector: &[f32], top_k: usize, filter: JsValue) -> Result<JsValue, JsValue> {
+        let filter: Option<Filter> = from_js(filter)?;
+        let results = self.with_collection_mut(collection, |engine| engine.search_filtered(query_vector, top_k, filter.as_ref()))?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    /// `scope`는 `{ include: string[], exclude: string[] }` 형태의 객체입니다.
+    pub fn search_scoped(&self, collection: &str, query_vector: &[f32], top_k: usize, scope: JsValue) -> Result<JsValue, JsValue> {
+        let scope: PathScope = from_js(scope)?;
+        let results = self.with_collection(collection, |engine| engine.search_scoped(query_vector, top_k, &scope))?;
+        Ok(serde_wasm_bindgen::to_value(&results).unwrap())
+    }
+
+    /// `config`는 `{ policy, max_entries, max_bytes, ttl_ms }` 형태이며 생략한 값은 기본값을 사용합니다.
+    pub fn configure_query_cache(&mut self, collection: &str, config: JsValue) -> Result<(), JsValue> {
+        let config: CacheConfig = from_js(config)?;
+        self.with_collection_mut(collection, |engine| {
+            engine.configure_query_cache(config);
+            Ok(())
+        })
+    }
+
+    pub fn query_cache_stats(&self, collection: &str) -> Result<WasmCacheStats, JsValue> {
+        self.with_collection(collection, |engine| Ok(WasmCacheStats::from(engine.query_cache_stats())))
+    }
+
+    pub fn clear_query_cache(&mut self, collection: &str) -> Result<(), JsValue> {
+        self.with_collection_mut(collection, |engine| {
+            engine.clear_query_cache();
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use rust_vector_engine::models::{Database, Metric, VectorEngineError};
+
+#[test]
+fn test_database_collection_management() {
+    let mut db = Database::new();
+    db.create_collection("notes", 3, Metric::Cosine).unwrap();
+    db.create_collection("chat", 2, Metric::Euclidean).unwrap();
+    assert!(matches!(db.create_collection("notes", 4, Metric::Cosine), Err(VectorEngineError::AlreadyExists(_))));
+
+    db.rename_collection("chat", "messages").unwrap();
+    assert_eq!(db.list_collections(), vec!["messages".to_string(), "notes".to_string()]);
+    assert!(matches!(db.rename_collection("messages", "notes"), Err(VectorEngineError::AlreadyExists(_))));
+    assert!(matches!(db.rename_collection("chat", "other"), Err(VectorEngineError::ItemNotFound(_))));
+
+    // 컬렉션마다 차원을 따로 검사
+    assert!(db.collection_mut("messages").unwrap().add_document(1, vec![1.0, 2.0]).is_ok());
+    assert!(db.collection_mut("notes").unwrap().add_document(1, vec![1.0, 2.0]).is_err());
+
+    db.drop_collection("messages").unwrap();
+    assert!(matches!(db.collection("messages"), Err(VectorEngineError::ItemNotFound(_))));
+    assert!(db.drop_collection("messages").is_err());
+}
+
+#[test]
+fn test_database_snapshot_round_trip() {
+    let mut db = Database::new();
+    let notes = db.create_collection("notes", 2, Metric::Cosine).unwrap();
+    notes.add_document(1, vec![1.0, 0.0]).unwrap();
+    notes.add_document(2, vec![0.0, 1.0]).unwrap();
+    let chat = db.create_collection("chat", 3, Metric::Euclidean).unwrap();
+    chat.add_document(7, vec![0.0, 0.0, 0.0]).unwrap();
+    chat.add_document(8, vec![10.0, 0.0, 0.0]).unwrap();
+
+    let bytes = db.save_to_bytes().unwrap();
+    let mut restored = Database::load_from_bytes(&bytes).unwrap();
+    assert_eq!(restored.list_collections(), vec!["chat".to_string(), "notes".to_string()]);
+
+    let chat = restored.collection_mut("chat").unwrap();
+    assert_eq!(chat.dimension(), 3);
+    assert_eq!(chat.metric(), Metric::Euclidean);
+    let results = chat.search(&[9.0, 0.0, 0.0], 1).unwrap();
+    assert_eq!(results[0].0, 8);
+
+    let notes = restored.collection_mut("notes").unwrap();
+    assert_eq!(notes.document_count(), 2);
+    assert_eq!(notes.search(&[0.9, 0.1], 1).unwrap()[0].0, 1);
+}
+
+#[test]
+fn test_federated_search_normalizes_scores_across_metrics() {
+    let mut db = Database::new();
+    let notes = db.create_collection("notes", 2, Metric::Cosine).unwrap();
+    notes.add_document(1, vec![1.0, 0.0]).unwrap();
+    notes.add_document(2, vec![0.0, 1.0]).unwrap();
+    let files = db.create_collection("files", 2, Metric::Euclidean).unwrap();
+    files.add_document(1, vec![0.9, 0.1]).unwrap();
+    files.add_document(2, vec![-5.0, -5.0]).unwrap();
+    db.create_collection("wide", 3, Metric::Cosine).unwrap();
+
+    let targets = vec!["notes".to_string(), "files".to_string()];
+    let hits = db.federated_search(&targets, &[1.0, 0.0], 3).unwrap();
+    assert_eq!(hits.len(), 3);
+    assert_eq!((hits[0].collection.as_str(), hits[0].id), ("notes", 1));
+    assert_eq!((hits[1].collection.as_str(), hits[1].id), ("files", 1));
+    assert!(hits.windows(2).all(|w| w[0].score >= w[1].score));
+    assert!(hits.iter().all(|h| h.score > 0.0 && h.score <= 1.0));
+
+    // 같은 컬렉션을 두 번 지정해도 결과가 중복되지 않음
+    let repeated = vec!["notes".to_string(), "notes".to_string()];
+    let hits = db.federated_search(&repeated, &[1.0, 0.0], 4).unwrap();
+    assert_eq!(hits.len(), 2);
+
+    // 차원이 다른 컬렉션이나 없는 컬렉션이 섞이면 오류
+    let mixed = vec!["notes".to_string(), "wide".to_string()];
+    match db.federated_search(&mixed, &[1.0, 0.0], 3) {
+        Err(VectorEngineError::DimensionMismatch(message)) => assert!(message.contains("wide")),
+        other => panic!("차원 불일치 오류가 필요합니다: {:?}", other.map(|hits| hits.len())),
+    }
+    // 연합 검색은 각 컬렉션의 쿼리 캐시를 건드리지 않음
+    let stats = db.collection("notes").unwrap().query_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (0, 0, 0));
+    let missing = vec!["nope".to_string()];
+    assert!(matches!(db.federated_search(&missing, &[1.0, 0.0], 3), Err(VectorEngineError::ItemNotFound(_))));
+}
@@ -0,0 +1,32 @@
+use rust_vector_engine::models::{Filter, Payload};
+use serde_json::json;
+
+fn payload(value: serde_json::Value) -> Payload {
+    value.as_object().unwrap().clone()
+}
+
+#[test]
+fn test_filter_nested_range_and_array_match() {
+    let p = payload(json!({ "tags": ["work", "2025"], "meta": { "stars": 4 } }));
+
+    let in_range = Filter::Range { key: "meta.stars".into(), gte: Some(3.0), lte: None };
+    let tagged = Filter::Match { key: "tags".into(), value: json!("work") };
+    assert!(Filter::And(vec![in_range, tagged]).matches(Some(&p), None));
+
+    let out_of_range = Filter::Range { key: "meta.stars".into(), gte: None, lte: Some(3.0) };
+    assert!(!out_of_range.matches(Some(&p), None));
+    // 메타데이터가 없는 문서는 필드 조건을 만족하지 않음
+    assert!(!Filter::Exists { key: "tags".into() }.matches(None, None));
+}
+
+#[test]
+fn test_filter_path_prefix_and_json_form() {
+    let filter: Filter = serde_json::from_value(json!({
+        "not": { "path_prefix": "Projects/2025" }
+    }))
+    .unwrap();
+
+    assert!(!filter.matches(None, Some("Projects/2025/plan.md")));
+    assert!(filter.matches(None, Some("Projects/2025-old/plan.md")));
+    assert!(filter.matches(None, None));
+}
@@ -0,0 +1,126 @@
+use rust_vector_engine::models::{CacheConfig, CacheWeight, EvictionPolicy, SearchCache};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+fn cache(policy: EvictionPolicy, max_entries: usize) -> SearchCache<'static, u64, Vec<u64>> {
+    SearchCache::with_config(CacheConfig { policy, max_entries, ..CacheConfig::default() })
+}
+
+#[test]
+fn test_eviction_policies_choose_different_victims() {
+    // LRU: 최근에 조회한 1은 남고 2가 제거됨
+    let mut lru = cache(EvictionPolicy::Lru, 2);
+    lru.put(1, vec![1]);
+    lru.put(2, vec![2]);
+    lru.get(&1);
+    lru.put(3, vec![3]);
+    assert!(lru.contains(&1) && !lru.contains(&2));
+
+    // LFU: 여러 번 조회된 1은 이후 2를 조회해도 남음
+    let mut lfu = cache(EvictionPolicy::Lfu, 2);
+    lfu.put(1, vec![1]);
+    lfu.put(2, vec![2]);
+    lfu.get(&1);
+    lfu.get(&1);
+    lfu.get(&2);
+    lfu.put(3, vec![3]);
+    assert!(lfu.contains(&1) && !lfu.contains(&2));
+
+    // 2Q: 재사용된 1은 한 번씩만 들어온 스캔 항목들에 밀려나지 않음
+    let mut two_queue = cache(EvictionPolicy::TwoQueue, 3);
+    two_queue.put(1, vec![1]);
+    two_queue.get(&1);
+    for key in 10..20 {
+        two_queue.put(key, vec![key]);
+    }
+    assert!(two_queue.contains(&1));
+    assert_eq!(two_queue.len(), 3);
+    assert_eq!(two_queue.stats().evictions, 8);
+    assert_eq!(two_queue.stats().insertions, 11);
+}
+
+static NOW: AtomicU64 = AtomicU64::new(0);
+
+fn fake_clock() -> u64 {
+    NOW.load(Ordering::SeqCst)
+}
+
+#[test]
+fn test_byte_capacity_and_ttl() {
+    let entry_bytes = vec![0u64; 4].weight();
+    let config = CacheConfig {
+        max_entries: 100,
+        max_bytes: Some(entry_bytes * 2),
+        ttl_ms: Some(1_000),
+        ..CacheConfig::default()
+    };
+    let mut cache: SearchCache<'static, u64, Vec<u64>> = SearchCache::with_config(config).with_clock(fake_clock);
+
+    cache.put(1, vec![0; 4]);
+    cache.put(2, vec![0; 4]);
+    assert_eq!(cache.stats().bytes, entry_bytes * 2);
+    cache.put(3, vec![0; 4]);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.stats().evictions, 1);
+    // 값 하나가 바이트 상한보다 크면 저장하지 않음
+    cache.put(4, vec![0; 64]);
+    assert!(!cache.contains(&4));
+
+    NOW.store(1_000, Ordering::SeqCst);
+    // 만료된 항목은 contains와 peek 모두에서 보이지 않음
+    assert!(!cache.contains(&3) && cache.peek(&3).is_none());
+    assert!(cache.get(&3).is_none());
+    assert_eq!(cache.stats().expirations, 1);
+    assert_eq!(cache.stats().misses, 1);
+    assert_eq!(cache.stats().bytes, entry_bytes);
+}
+
+/// 크기가 0으로 추정되는 값
+struct Marker;
+
+impl CacheWeight for Marker {
+    fn weight(&self) -> usize {
+        0
+    }
+}
+
+#[test]
+fn test_zero_weight_values_respect_max_entries() {
+    for policy in [EvictionPolicy::Lru, EvictionPolicy::Lfu, EvictionPolicy::TwoQueue] {
+        let mut cache: SearchCache<'static, u64, Marker> =
+            SearchCache::with_config(CacheConfig { policy, max_entries: 2, ..CacheConfig::default() });
+        for key in 0..5 {
+            cache.put(key, Marker);
+        }
+        assert_eq!(cache.len(), 2, "{:?}", policy);
+        assert!(cache.contains(&3) && cache.contains(&4));
+    }
+}
+
+#[test]
+fn test_two_queue_scan_keeps_hot_set_and_readmits_recent_keys() {
+    // 용량 8: A1in 2개, A1out 4개
+    let mut cache = cache(EvictionPolicy::TwoQueue, 8);
+    for key in 0..6 {
+        cache.put(key, vec![key]);
+        cache.get(&key);
+    }
+    for key in 100..200 {
+        cache.put(key, vec![key]);
+    }
+    // 한 번씩만 들어온 스캔은 A1in 안에서만 교체됨
+    assert!((0..6).all(|key| cache.contains(&key)));
+    assert!(cache.contains(&199) && cache.contains(&198) && !cache.contains(&197));
+    assert_eq!(cache.len(), 8);
+
+    // A1in에서 막 밀려난 키는 다시 저장되면 Am으로 들어가 다음 스캔에도 남음
+    cache.put(197, vec![197]);
+    for key in 200..300 {
+        cache.put(key, vec![key]);
+    }
+    assert!(cache.contains(&197));
+    // 오래전에 밀려나 A1out에서도 잊힌 키는 다시 A1in으로 들어감
+    cache.put(100, vec![100]);
+    cache.put(300, vec![300]);
+    cache.put(301, vec![301]);
+    assert!(!cache.contains(&100));
+}
@@ -0,0 +1,178 @@
+use prost::Message;
+use rust_vector_engine::models::document::{DeltaSnapshot, Document, NamedVector};
+use rust_vector_engine::models::{Metric, SharedBuffer, VectorEngine, VectorEngineError};
+use serde_json::json;
+
+/// 델타를 만들고 저장에 성공했다고 보고 확정
+fn save_and_commit(engine: &mut VectorEngine) -> Vec<u8> {
+    let delta = engine.save_delta().unwrap();
+    engine.commit_delta(engine.snapshot_generation() + 1).unwrap();
+    delta
+}
+
+#[test]
+fn test_delta_chain_matches_full_snapshot() {
+    let mut engine = VectorEngine::new(2);
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.0, 1.0]).unwrap();
+    engine.add_document(3, vec![1.0, 1.0]).unwrap();
+    let base = engine.compact().unwrap();
+    assert!(!engine.has_unsaved_changes());
+
+    engine.update_document(&2, vec![0.5, 1.0]).unwrap();
+    engine.create_vector_space("title", 3, Metric::Cosine).unwrap();
+    engine.set_named_vector(&2, "title", vec![0.0, 1.0, 0.0]).unwrap();
+    let first = save_and_commit(&mut engine);
+
+    engine.delete_document(&1).unwrap();
+    engine.set_payload(&3, json!({ "tag": "draft" }).as_object().unwrap().clone()).unwrap();
+    let id = engine.insert(vec![0.2, 0.8]).unwrap();
+    engine.add_token_document(7, vec![vec![1.0, 0.0]]).unwrap();
+    let second = save_and_commit(&mut engine);
+    assert!(!engine.has_unsaved_changes());
+
+    // 델타는 바뀐 문서만 담으므로 전체 스냅샷보다 작음
+    assert!(second.len() < engine.save_to_bytes().unwrap().len());
+
+    let mut restored = VectorEngine::load_with_deltas(&base, &[first, second], 2).unwrap();
+    assert_eq!(restored.snapshot_generation(), 2);
+    assert_eq!(restored.document_count(), 3);
+    assert!(!restored.contains(&1));
+    for doc_id in [2, 3, id] {
+        assert_eq!(restored.get(&doc_id), engine.get(&doc_id));
+    }
+    assert_eq!(restored.token_document(&7), engine.token_document(&7));
+    assert_eq!(restored.insert(vec![1.0, 0.0]).unwrap(), engine.insert(vec![1.0, 0.0]).unwrap());
+
+    // 압축 후에는 새 기준 스냅샷 하나로 같은 상태를 복원
+    let compacted = engine.compact().unwrap();
+    let reloaded = VectorEngine::load_from_bytes(&compacted, 2).unwrap();
+    assert_eq!(reloaded.document_count(), engine.document_count());
+    assert_eq!(reloaded.snapshot_generation(), engine.snapshot_generation());
+}
+
+#[test]
+fn test_delta_rejects_broken_chain() {
+    let mut engine = VectorEngine::new(2);
+    let base = engine.compact().unwrap();
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+    let first = save_and_commit(&mut engine);
+    engine.add_document(2, vec![0.0, 1.0]).unwrap();
+    let second = save_and_commit(&mut engine);
+
+    // 중간 델타가 빠진 경우
+    let result = VectorEngine::load_with_deltas(&base, std::slice::from_ref(&second), 2);
+    assert!(matches!(result, Err(VectorEngineError::DeserializationError(_))));
+
+    // 같은 델타를 두 번 적용할 수 없음
+    let mut restored = VectorEngine::load_with_deltas(&base, &[first.clone(), second], 2).unwrap();
+    assert!(restored.apply_delta(&first).is_err());
+    assert_eq!(restored.document_count(), 2);
+}
+
+#[test]
+fn test_uncommitted_delta_keeps_changes_for_next_delta() {
+    let mut engine = VectorEngine::new(2);
+    let base = engine.compact().unwrap();
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+
+    // 저장에 실패하여 확정하지 못한 델타
+    engine.save_delta().unwrap();
+    assert_eq!(engine.snapshot_generation(), 0);
+    assert!(engine.has_unsaved_changes());
+
+    // 델타를 만든 뒤의 변경은 확정 후에도 다음 델타로 넘어감
+    let delta = engine.save_delta().unwrap();
+    engine.add_document(2, vec![0.0, 1.0]).unwrap();
+    assert!(engine.commit_delta(5).is_err());
+    engine.commit_delta(1).unwrap();
+    assert!(engine.has_unsaved_changes());
+    let next = save_and_commit(&mut engine);
+
+    let restored = VectorEngine::load_with_deltas(&base, &[delta, next], 2).unwrap();
+    assert!(restored.contains(&1) && restored.contains(&2));
+}
+
+#[test]
+fn test_checkpoint_clears_change_tracking() {
+    let mut engine = VectorEngine::new(2);
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+    let snapshot = engine.checkpoint(SharedBuffer::new()).unwrap();
+    assert!(!engine.has_unsaved_changes());
+
+    engine.add_document(2, vec![0.0, 1.0]).unwrap();
+    let delta = DeltaSnapshot::decode(save_and_commit(&mut engine).as_slice()).unwrap();
+    assert_eq!(delta.upserts.iter().map(|doc| doc.id).collect::<Vec<_>>(), vec![2]);
+    let restored = VectorEngine::load_with_deltas(&snapshot, &[delta.encode_to_vec()], 2).unwrap();
+    assert_eq!(restored.document_count(), 2);
+}
+
+#[test]
+fn test_invalid_delta_changes_nothing() {
+    let mut engine = VectorEngine::new(2);
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+    let base = engine.compact().unwrap();
+
+    let document = |id: u64, named_vectors: Vec<NamedVector>| Document {
+        id,
+        vector: vec![0.0, 1.0],
+        named_vectors,
+        ..Default::default()
+    };
+    let delta = DeltaSnapshot {
+        format_version: 1,
+        base_generation: 0,
+        generation: 1,
+        deletes: vec![1],
+        upserts: vec![
+            document(2, Vec::new()),
+            document(3, vec![NamedVector { name: "title".to_string(), vector: vec![1.0] }]),
+        ],
+        ..Default::default()
+    };
+
+    let mut restored = VectorEngine::load_from_bytes(&base, 2).unwrap();
+    assert!(restored.apply_delta(&delta.encode_to_vec()).is_err());
+    assert_eq!(restored.snapshot_generation(), 0);
+    assert!(restored.contains(&1) && !restored.contains(&2));
+    assert_eq!(restored.search(&[1.0, 0.0], 1).unwrap()[0].0, 1);
+}
+
+#[test]
+fn test_full_save_clears_change_tracking() {
+    let mut engine = VectorEngine::new(2);
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+    let base = engine.save_to_bytes().unwrap();
+    assert!(!engine.has_unsaved_changes());
+
+    engine.add_document(2, vec![0.0, 1.0]).unwrap();
+    let delta = DeltaSnapshot::decode(save_and_commit(&mut engine).as_slice()).unwrap();
+    assert_eq!(delta.upserts.iter().map(|doc| doc.id).collect::<Vec<_>>(), vec![2]);
+    let restored = VectorEngine::load_with_deltas(&base, &[delta.encode_to_vec()], 2).unwrap();
+    assert_eq!(restored.document_count(), 2);
+}
+
+#[test]
+fn test_delta_replaces_recreated_vector_space() {
+    let mut engine = VectorEngine::new(2);
+    engine.create_vector_space("title", 3, Metric::Cosine).unwrap();
+    engine.create_vector_space("body", 2, Metric::Cosine).unwrap();
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+    engine.set_named_vector(&1, "title", vec![1.0, 0.0, 0.0]).unwrap();
+    engine.set_named_vector(&1, "body", vec![0.0, 1.0]).unwrap();
+    let base = engine.save_to_bytes().unwrap();
+
+    // 다른 차원으로 다시 만든 공간과, 같은 설정으로 다시 만든 공간
+    engine.drop_vector_space("title").unwrap();
+    engine.create_vector_space("title", 4, Metric::Euclidean).unwrap();
+    engine.set_named_vector(&1, "title", vec![0.0, 0.0, 0.0, 1.0]).unwrap();
+    engine.drop_vector_space("body").unwrap();
+    engine.create_vector_space("body", 2, Metric::Cosine).unwrap();
+    let delta = save_and_commit(&mut engine);
+
+    let restored = VectorEngine::load_with_deltas(&base, &[delta], 2).unwrap();
+    let title = restored.vector_space("title").unwrap();
+    assert_eq!((title.dimension(), title.metric()), (4, Metric::Euclidean));
+    assert_eq!(restored.named_vector(&1, "title"), engine.named_vector(&1, "title"));
+    assert!(restored.named_vector(&1, "body").is_none());
+}
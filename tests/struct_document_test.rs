@@ -1,7 +1,7 @@
 use prost::Message;
 use std::fs::File;
 use std::io::{Read, Write};
-use rust_vector_engine::models::Document;
+use rust_vector_engine::models::{Document, SparseVector};
 
 // document 생성자 new 테스트
 #[test]
@@ -94,3 +94,13 @@ fn test_save_and_load_document_to_file() {
     std::fs::remove_file(test_file_path).unwrap();
 }
 
+
+// SparseVector 생성 시 정렬 및 중복 인덱스 병합 테스트
+#[test]
+fn test_sparse_vector_normalizes_indices() {
+    let sparse = SparseVector::new(vec![7, 2, 7, 5], vec![0.5, 1.0, 0.25, 0.0]).unwrap();
+
+    assert_eq!(sparse.indices, vec![2, 7]);
+    assert_eq!(sparse.values, vec![1.0, 0.75]);
+    assert!(SparseVector::new(vec![1, 2], vec![1.0]).is_none());
+}
@@ -0,0 +1,30 @@
+use rust_vector_engine::models::TextIndex;
+use rust_vector_engine::models::text_index::tokenize;
+
+#[test]
+fn test_tokenize_mixed_korean_and_english() {
+    assert_eq!(
+        tokenize("벡터검색 Engine_v2, 나!"),
+        vec!["벡터", "터검", "검색", "engine_v2", "나"]
+    );
+}
+
+#[test]
+fn test_bm25_ranks_rare_terms_higher() {
+    let mut index = TextIndex::new();
+    index.insert(1, "rust vector engine");
+    index.insert(2, "rust web server");
+    index.insert(3, "오늘의 회의록 정리");
+
+    // "vector"는 1번 문서에만 있으므로 1번이 최상위여야 함
+    let results = index.search("rust vector", 3);
+    assert_eq!(results[0].0, 1);
+    assert_eq!(results.len(), 2);
+
+    // 조사가 붙은 한국어 질의도 bigram으로 매칭되어야 함
+    assert_eq!(index.search("회의록을", 3)[0].0, 3);
+
+    index.remove(1);
+    assert!(index.search("vector", 3).is_empty());
+    assert_eq!(index.len(), 2);
+}
@@ -1,5 +1,10 @@
+// 기존 테스트의 `&vec![..]` 질의 인자는 그대로 둠
+#![allow(clippy::useless_vec)]
+
+use rust_vector_engine::models::document::EngineState;
 use rust_vector_engine::models::errors::VectorEngineError;
-use rust_vector_engine::models::VectorEngine;
+use rust_vector_engine::models::{Filter, FusionMethod, GroupAggregate, Metric, PathScope, RecommendStrategy, SparseVector, UpsertOutcome, VectorEngine};
+use serde_json::json;
 
 // 테스트에 사용할 기본 파라미터
 const TEST_DIM: usize = 3;
@@ -95,6 +100,165 @@ fn test_search_after_cache_invalidation() {
     // 검증: 캐시의 낡은 데이터(ID 1)가 아닌, 새로 추가된 ID 2가 반환되어야 함
     assert_eq!(second_results[0].0, closer_doc_id);
 }
+
+#[test]
+fn test_cache_key_respects_top_k_and_filter() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.9, 0.1, 0.0]).unwrap();
+    engine.add_document(3, vec![0.5, 0.5, 0.0]).unwrap();
+    engine.set_payload(&2, json!({ "tag": "work" }).as_object().unwrap().clone()).unwrap();
+    let query_vector = vec![1.0, 0.0, 0.0];
+
+    // 작은 top_k로 캐시된 결과가 더 큰 top_k 요청에 쓰이지 않음
+    assert_eq!(engine.search(&query_vector, 1).unwrap().len(), 1);
+    assert_eq!(engine.search(&query_vector, 3).unwrap().len(), 3);
+    assert_eq!(engine.query_cache_stats().misses, 2);
+    // 더 큰 top_k의 결과에서 작은 top_k를 잘라서 응답
+    assert_eq!(engine.search(&query_vector, 2).unwrap().iter().map(|r| r.0).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(engine.query_cache_stats().hits, 1);
+
+    // 필터가 다르면 별도의 항목
+    let work = Filter::Match { key: "tag".into(), value: json!("work") };
+    let results = engine.search_filtered(&query_vector, 3, Some(&work)).unwrap();
+    assert_eq!(results.iter().map(|r| r.0).collect::<Vec<_>>(), vec![2]);
+    assert_eq!(engine.query_cache_len(), 2);
+
+    // 메타데이터 변경으로 필터 조건을 새로 만족하게 된 문서도 반영됨
+    engine.set_payload(&3, json!({ "tag": "work" }).as_object().unwrap().clone()).unwrap();
+    let results = engine.search_filtered(&query_vector, 3, Some(&work)).unwrap();
+    assert_eq!(results.iter().map(|r| r.0).collect::<Vec<_>>(), vec![2, 3]);
+}
+
+#[test]
+fn test_query_cache_controls_and_stats() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.0, 1.0, 0.0]).unwrap();
+
+    // 미리 채운 질의는 첫 검색부터 적중
+    let queries = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]];
+    assert_eq!(engine.warm_query_cache(&queries, 2).unwrap(), 2);
+    assert_eq!(engine.warm_query_cache(&queries, 1).unwrap(), 0);
+    engine.search(&queries[0], 2).unwrap();
+    let stats = engine.query_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 0, 2));
+    assert_eq!(stats.hit_rate(), 100.0);
+
+    // 무효화와 비우기에도 통계는 유지되고, 따로 초기화할 수 있음
+    engine.update_document(&1, vec![0.9, 0.1, 0.0]).unwrap();
+    engine.clear_query_cache();
+    assert_eq!(engine.query_cache_stats().hits, 1);
+    assert_eq!(engine.query_cache_stats().entries, 0);
+    engine.reset_query_cache_stats();
+    assert_eq!(engine.query_cache_stats().hits, 0);
+
+    engine.resize_query_cache(1);
+    engine.search(&queries[0], 1).unwrap();
+    engine.search(&queries[1], 1).unwrap();
+    assert_eq!(engine.query_cache_len(), 1);
+    assert_eq!(engine.query_cache_stats().evictions, 1);
+
+    // 끄면 비워지고 검색 결과를 저장하지 않음
+    engine.set_query_cache_enabled(false);
+    engine.search(&queries[0], 1).unwrap();
+    assert_eq!(engine.query_cache_len(), 0);
+    assert_eq!(engine.query_cache_stats().misses, 2);
+}
+
+#[test]
+fn test_embedding_cache_skips_unchanged_text_and_persists() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    let embed_calls = std::cell::RefCell::new(Vec::new());
+    let embedder = |texts: &[&str]| {
+        embed_calls.borrow_mut().push(texts.len());
+        Ok(texts.iter().map(|t| vec![t.len() as f32, 1.0, 0.0]).collect())
+    };
+
+    let report = engine.add_text_documents(&[(1, "alpha"), (2, "beta"), (3, "alpha")], embedder).unwrap();
+    assert_eq!((report.cache_hits, report.embedded), (0, 2));
+    assert_eq!(engine.document_text(&3), Some("alpha"));
+    // 배치 안에서 반복된 텍스트는 한 번만 조회되어 미스도 한 번만 기록됨
+    assert_eq!(engine.embedding_cache_stats().misses, 2);
+
+    // 바뀌지 않은 텍스트는 임베더를 거치지 않음
+    let report = engine.add_text_documents(&[(1, "alpha"), (2, "beta v2")], embedder).unwrap();
+    assert_eq!((report.cache_hits, report.embedded), (1, 1));
+    assert_eq!((engine.embedding_cache_stats().hits, engine.embedding_cache_stats().misses), (1, 3));
+    assert_eq!(*embed_calls.borrow(), vec![2, 1]);
+    assert_eq!(engine.uncached_texts(&["alpha", "gamma", "gamma"]), vec!["gamma"]);
+
+    // 스냅샷에 함께 저장되어 복원 후에도 적중
+    let bytes = engine.save_to_bytes().unwrap();
+    let mut restored = VectorEngine::load_from_bytes(&bytes, TEST_DIM).unwrap();
+    assert_eq!(restored.cached_embedding("beta v2"), Some(vec![7.0, 1.0, 0.0]));
+    assert_eq!(restored.embedding_cache_stats().hits, 1);
+    assert_eq!(restored.embedding_cache_stats().entries, 3);
+
+    // 해시가 같아도 원문이 다른 항목은 다른 텍스트의 임베딩으로 쓰이지 않음
+    let mut state = engine.to_state();
+    state.embedding_cache[0].text = "forged".to_string();
+    let restored = VectorEngine::from_state(state, TEST_DIM).unwrap();
+    assert_eq!(restored.embedding_cache_stats().entries, 2);
+
+    // 임베더가 돌려준 벡터 수가 맞지 않으면 InvalidInput
+    let res = engine.add_text_documents(&[(4, "delta")], |_| Ok(Vec::new()));
+    assert!(matches!(res.unwrap_err(), VectorEngineError::InvalidInput(_)));
+}
+
+#[test]
+fn test_semantic_cache_reuses_near_identical_queries() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.0, 1.0, 0.0]).unwrap();
+    engine.enable_semantic_cache(16, 0.99);
+
+    let first = engine.search(&[1.0, 0.1, 0.0], 1).unwrap();
+    // 조금 다른 질의는 근사 적중
+    let second = engine.search(&[1.0, 0.12, 0.0], 1).unwrap();
+    assert_eq!(first[0].0, second[0].0);
+    // 근사 적중의 거리는 저장된 질의가 아닌 현재 질의 기준으로 다시 계산됨
+    assert_ne!(first[0].1, second[0].1);
+    assert_eq!(second[0].1, Metric::Cosine.distance(&[1.0, 0.12, 0.0], &[1.0, 0.0, 0.0]));
+    // 완전히 같은 질의는 정확 적중
+    engine.search(&[1.0, 0.1, 0.0], 1).unwrap();
+    // 유사도가 임계값보다 낮으면 미스
+    assert_eq!(engine.search(&[0.1, 1.0, 0.0], 1).unwrap()[0].0, 2);
+
+    let stats = engine.semantic_cache_stats().unwrap();
+    assert_eq!((stats.exact_hits, stats.approximate_hits, stats.misses), (1, 1, 2));
+
+    // 쓰기로 무효화된 결과는 근사 적중에도 쓰이지 않음
+    engine.add_document(3, vec![1.0, 0.1, 0.0]).unwrap();
+    assert_eq!(engine.search(&[1.0, 0.100002, 0.0], 1).unwrap()[0].0, 3);
+}
+
+#[test]
+fn test_cache_survives_unrelated_writes() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.9, 0.1, 0.0]).unwrap();
+    engine.add_document(3, vec![0.0, 0.0, 1.0]).unwrap();
+    let query_vector = vec![1.0, 0.05, 0.0];
+    engine.search(&query_vector, 2).unwrap();
+
+    // 결과(1, 2)보다 먼 문서의 추가·갱신·삭제는 캐시 항목을 지우지 않음
+    engine.add_document(4, vec![0.0, 1.0, 0.0]).unwrap();
+    engine.update_document(&3, vec![0.0, 0.1, 1.0]).unwrap();
+    engine.delete_document(&4).unwrap();
+    assert_eq!(engine.query_cache_len(), 1);
+    engine.search(&query_vector, 2).unwrap();
+    assert_eq!(engine.query_cache_stats().hits, 1);
+
+    // 결과에 포함된 문서를 갱신하면 해당 항목이 무효화됨
+    engine.update_document(&2, vec![0.0, 1.0, 0.1]).unwrap();
+    assert_eq!(engine.query_cache_len(), 0);
+    let results = engine.search(&query_vector, 2).unwrap();
+    assert_eq!(results.iter().map(|r| r.0).collect::<Vec<_>>(), vec![1, 2]);
+    // 통계는 무효화 이후에도 유지됨
+    assert_eq!(engine.query_cache_stats().misses, 2);
+}
+
 #[test]
 fn test_delete_and_rebuild() {
     let mut engine = VectorEngine::new(TEST_DIM);
@@ -159,4 +323,543 @@ fn test_returns_item_not_found_error() {
     let mut engine = VectorEngine::new(TEST_DIM);
     let result = engine.delete_document(&99);
     assert!(matches!(result.unwrap_err(), VectorEngineError::ItemNotFound(_)));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_search_scoped_by_path_prefix() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document_with_path(1, vec![1.0, 0.0, 0.0], "Projects/2025/plan.md").unwrap();
+    engine.add_document_with_path(2, vec![0.9, 0.1, 0.0], "Projects/2024/plan.md").unwrap();
+    engine.add_document_with_path(3, vec![0.8, 0.2, 0.0], "Projects/2025-old/plan.md").unwrap();
+    engine.add_document(4, vec![0.7, 0.3, 0.0]).unwrap();
+
+    let query_vector = vec![1.0, 0.0, 0.0];
+
+    // include: "Projects/2025" 폴더 안의 문서만 (2025-old는 제외되어야 함)
+    let results = engine.search_scoped(&query_vector, 10, &PathScope::include(["Projects/2025/"])).unwrap();
+    assert_eq!(results.iter().map(|r| r.0).collect::<Vec<_>>(), vec![1]);
+
+    // exclude: "Projects" 아래 전체를 제외하면 경로가 없는 문서만 남음
+    let results = engine.search_scoped(&query_vector, 10, &PathScope::exclude(["Projects"])).unwrap();
+    assert_eq!(results.iter().map(|r| r.0).collect::<Vec<_>>(), vec![4]);
+}
+
+#[test]
+fn test_document_path_round_trip_and_delete() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document_with_path(1, vec![1.0, 0.0, 0.0], "Inbox/a.md").unwrap();
+    engine.add_document_with_path(2, vec![0.0, 1.0, 0.0], "Inbox/b.md").unwrap();
+
+    let reloaded = VectorEngine::load_from_bytes(&engine.save_to_bytes().unwrap(), TEST_DIM).unwrap();
+    assert_eq!(reloaded.document_path(&1), Some("Inbox/a.md"));
+
+    // 삭제된 문서는 범위 검색 결과에서도 사라져야 함
+    engine.delete_document(&1).unwrap();
+    let results = engine.search_scoped(&[1.0, 0.0, 0.0], 10, &PathScope::include(["Inbox"])).unwrap();
+    assert_eq!(results.iter().map(|r| r.0).collect::<Vec<_>>(), vec![2]);
+}
+
+#[test]
+fn test_string_keys_search_and_rename() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    let id_a = engine.add_document_by_key("notes/a.md", vec![1.0, 0.0, 0.0]).unwrap();
+    let id_b = engine.add_document_by_key("notes/b.md", vec![0.0, 1.0, 0.0]).unwrap();
+    assert_ne!(id_a, id_b);
+    // 같은 키로 다시 추가하면 같은 내부 ID를 덮어씀
+    assert_eq!(engine.add_document_by_key("notes/a.md", vec![0.9, 0.1, 0.0]).unwrap(), id_a);
+    assert_eq!(engine.document_count(), 2);
+
+    let results = engine.search_keys(&[1.0, 0.0, 0.0], 1).unwrap();
+    assert_eq!(results[0].0, "notes/a.md");
+
+    // 이름 변경 후에는 새 키로만 조회되고, 벡터는 그대로 유지
+    engine.rename_document("notes/a.md", "archive/a.md").unwrap();
+    assert_eq!(engine.document_id("notes/a.md"), None);
+    assert_eq!(engine.document_id("archive/a.md"), Some(id_a));
+    assert_eq!(engine.documents().get(&id_a).unwrap(), &vec![0.9, 0.1, 0.0]);
+
+    // 이미 쓰이는 키로는 명시적으로 덮어쓸 때만 이름을 바꿀 수 있음
+    let taken = engine.rename_document("notes/b.md", "archive/a.md");
+    assert!(matches!(taken.unwrap_err(), VectorEngineError::AlreadyExists(_)));
+    assert_eq!(engine.document_count(), 2);
+    engine.rename_document_replacing("notes/b.md", "archive/a.md").unwrap();
+    assert_eq!(engine.document_id("archive/a.md"), Some(id_b));
+    assert!(!engine.contains(&id_a));
+
+    let missing = engine.delete_document_by_key("notes/a.md");
+    assert!(matches!(missing.unwrap_err(), VectorEngineError::ItemNotFound(_)));
+}
+
+#[test]
+fn test_string_keys_persist_without_id_reuse() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document_by_key("a", vec![1.0, 0.0, 0.0]).unwrap();
+    let id_b = engine.add_document_by_key("b", vec![0.0, 1.0, 0.0]).unwrap();
+    engine.delete_document_by_key("b").unwrap();
+
+    let mut reloaded = VectorEngine::load_from_bytes(&engine.save_to_bytes().unwrap(), TEST_DIM).unwrap();
+    assert_eq!(reloaded.document_key(&0), Some("a"));
+
+    // 삭제된 문서의 내부 ID는 다시 할당되지 않아야 함
+    let id_c = reloaded.add_document_by_key("c", vec![0.0, 0.0, 1.0]).unwrap();
+    assert!(id_c > id_b);
+}
+
+#[test]
+fn test_insert_allocates_monotonic_ids() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    // 호출자가 직접 지정한 ID와 충돌하지 않아야 함
+    engine.add_document(1, vec![0.0, 0.0, 1.0]).unwrap();
+    let first = engine.insert(vec![1.0, 0.0, 0.0]).unwrap();
+    let second = engine.insert(vec![0.0, 1.0, 0.0]).unwrap();
+    assert_eq!((first, second), (0, 2));
+
+    engine.delete_document(&second).unwrap();
+    let mut reloaded = VectorEngine::load_from_bytes(&engine.save_to_bytes().unwrap(), TEST_DIM).unwrap();
+    let ids = reloaded.insert_batch(vec![vec![0.5, 0.5, 0.0], vec![0.0, 0.5, 0.5]]).unwrap();
+    assert_eq!(ids, vec![3, 4]);
+    assert_eq!(reloaded.document_count(), 4);
+}
+
+#[test]
+fn test_insert_after_max_explicit_id() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    // 해시처럼 큰 ID를 직접 지정해도 자동 할당은 작은 값에서 이어짐
+    engine.add_document(u64::MAX, vec![1.0, 0.0, 0.0]).unwrap();
+    engine.add_document(1 << 60, vec![0.0, 1.0, 0.0]).unwrap();
+    assert_eq!(engine.insert(vec![0.0, 0.0, 1.0]).unwrap(), 0);
+
+    // 할당할 ID가 바닥나면 패닉 대신 오류를 반환
+    let state = EngineState { next_id: u64::MAX, ..engine.to_state() };
+    let mut exhausted = VectorEngine::from_state(state, TEST_DIM).unwrap();
+    assert!(matches!(exhausted.insert(vec![1.0, 1.0, 0.0]), Err(VectorEngineError::InvalidInput(_))));
+    assert!(exhausted.insert_batch(vec![vec![1.0, 1.0, 0.0]]).is_err());
+    assert_eq!(exhausted.document_count(), 3);
+}
+
+#[test]
+fn test_insert_batch_rejects_dimension_mismatch() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    let res = engine.insert_batch(vec![vec![1.0, 0.0, 0.0], vec![1.0, 0.0]]);
+    assert!(matches!(res.unwrap_err(), VectorEngineError::DimensionMismatch(_)));
+    assert_eq!(engine.document_count(), 0);
+}
+
+#[test]
+fn test_upsert_reports_outcome() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    assert_eq!(engine.upsert(1, vec![1.0, 0.0, 0.0]).unwrap(), UpsertOutcome::Inserted);
+    assert_eq!(engine.upsert(1, vec![0.0, 1.0, 0.0]).unwrap(), UpsertOutcome::Updated);
+    assert_eq!(engine.documents().get(&1).unwrap(), &vec![0.0, 1.0, 0.0]);
+
+    // 엄격 모드는 이미 있는 ID를 거부해야 함
+    let res = engine.add_document_strict(1, vec![0.0, 0.0, 1.0]);
+    assert!(matches!(res.unwrap_err(), VectorEngineError::AlreadyExists(_)));
+    assert_eq!(engine.documents().get(&1).unwrap(), &vec![0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn test_set_and_patch_payload() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap();
+
+    let payload = json!({ "title": "회의록", "tags": ["work"], "meta": { "stars": 3 } });
+    engine.set_payload(&1, payload.as_object().unwrap().clone()).unwrap();
+
+    // null은 키 삭제, 중첩 객체는 병합
+    let patch = json!({ "title": null, "meta": { "read": true } });
+    engine.patch_payload(&1, patch.as_object().unwrap()).unwrap();
+    let expected = json!({ "tags": ["work"], "meta": { "stars": 3, "read": true } });
+    assert_eq!(engine.payload(&1).unwrap(), expected.as_object().unwrap());
+
+    let reloaded = VectorEngine::load_from_bytes(&engine.save_to_bytes().unwrap(), TEST_DIM).unwrap();
+    assert_eq!(reloaded.payload(&1), engine.payload(&1));
+
+    let missing = engine.set_payload(&99, Default::default());
+    assert!(matches!(missing.unwrap_err(), VectorEngineError::ItemNotFound(_)));
+}
+
+#[test]
+fn test_get_and_contains() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document_with_path(7, vec![1.0, 0.0, 0.0], "Inbox/a.md").unwrap();
+    engine.set_payload(&7, json!({ "title": "a" }).as_object().unwrap().clone()).unwrap();
+
+    assert!(engine.contains(&7));
+    assert!(!engine.contains(&8));
+    let record = engine.get(&7).unwrap();
+    assert_eq!(record.vector, vec![1.0, 0.0, 0.0]);
+    assert_eq!(record.payload.unwrap()["title"], "a");
+    assert_eq!(record.path.as_deref(), Some("Inbox/a.md"));
+    assert!(engine.get(&8).is_none());
+}
+
+#[test]
+fn test_scroll_pages_in_stable_order_with_filter() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    for id in [5, 1, 4, 2, 3] {
+        engine.add_document(id, vec![1.0, id as f32, 0.0]).unwrap();
+        let tag = if id % 2 == 1 { "odd" } else { "even" };
+        engine.set_payload(&id, json!({ "tag": tag }).as_object().unwrap().clone()).unwrap();
+    }
+
+    let first = engine.scroll(None, 2, None);
+    assert_eq!(first.documents.iter().map(|d| d.id).collect::<Vec<_>>(), vec![1, 2]);
+    let second = engine.scroll(first.next_cursor, 2, None);
+    assert_eq!(second.documents.iter().map(|d| d.id).collect::<Vec<_>>(), vec![3, 4]);
+    // limit이 0이면 커서를 그대로 돌려주어 끝으로 오인하지 않음
+    let empty = engine.scroll(second.next_cursor, 0, None);
+    assert!(empty.documents.is_empty());
+    assert_eq!(empty.next_cursor, second.next_cursor);
+    let last = engine.scroll(second.next_cursor, 2, None);
+    assert_eq!(last.documents.iter().map(|d| d.id).collect::<Vec<_>>(), vec![5]);
+    assert_eq!(last.next_cursor, None);
+
+    let odd = Filter::Match { key: "tag".into(), value: json!("odd") };
+    let page = engine.scroll(None, 10, Some(&odd));
+    assert_eq!(page.documents.iter().map(|d| d.id).collect::<Vec<_>>(), vec![1, 3, 5]);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_delete_where_removes_matching_documents() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document_with_path(1, vec![1.0, 0.0, 0.0], "Old/a.md").unwrap();
+    engine.add_document_with_path(2, vec![0.9, 0.1, 0.0], "Old/b.md").unwrap();
+    engine.add_document_with_path(3, vec![0.0, 1.0, 0.0], "New/c.md").unwrap();
+
+    let deleted = engine.delete_where(&Filter::PathPrefix("Old".into())).unwrap();
+    assert_eq!(deleted, 2);
+    assert_eq!(engine.document_count(), 1);
+    assert!(engine.search_scoped(&[1.0, 0.0, 0.0], 10, &PathScope::include(["Old"])).unwrap().is_empty());
+    assert_eq!(engine.search(&[1.0, 0.0, 0.0], 1).unwrap()[0].0, 3);
+}
+
+#[test]
+fn test_update_payload_where_renames_tag() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    for id in 1..=3 {
+        engine.add_document(id, vec![1.0, id as f32, 0.0]).unwrap();
+        let tag = if id < 3 { "draft" } else { "done" };
+        engine.set_payload(&id, json!({ "tag": tag }).as_object().unwrap().clone()).unwrap();
+    }
+
+    let filter = Filter::Match { key: "tag".into(), value: json!("draft") };
+    let patch = json!({ "tag": "wip" });
+    assert_eq!(engine.update_payload_where(&filter, patch.as_object().unwrap()).unwrap(), 2);
+    assert_eq!(engine.payload(&1).unwrap()["tag"], "wip");
+    assert_eq!(engine.payload(&3).unwrap()["tag"], "done");
+    assert_eq!(engine.update_payload_where(&filter, patch.as_object().unwrap()).unwrap(), 0);
+}
+
+#[test]
+fn test_hybrid_search_boosts_exact_keyword_match() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document_with_text(1, vec![1.0, 0.0, 0.0], "general notes about cooking").unwrap();
+    engine.add_document_with_text(2, vec![0.8, 0.6, 0.0], "how rebuild_index works").unwrap();
+    engine.add_document_with_text(3, vec![0.0, 1.0, 0.0], "travel plans").unwrap();
+
+    let query_vector = [1.0, 0.0, 0.0];
+    // 순수 벡터 검색이라면 1번이 먼저
+    assert_eq!(engine.hybrid_search("rebuild_index", &query_vector, 3, 1.0).unwrap()[0].0, 1);
+    // 키워드 비중을 높이면 정확한 함수 이름이 들어간 2번이 먼저
+    assert_eq!(engine.hybrid_search("rebuild_index", &query_vector, 3, 0.3).unwrap()[0].0, 2);
+
+    // 텍스트 색인은 스냅샷에 포함되어 재로딩 후에도 동작해야 함
+    let reloaded = VectorEngine::load_from_bytes(&engine.save_to_bytes().unwrap(), TEST_DIM).unwrap();
+    assert_eq!(reloaded.keyword_search("rebuild_index", 1)[0].0, 2);
+}
+
+#[test]
+fn test_search_mmr_skips_near_duplicates() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.99, 0.01, 0.0]).unwrap(); // 1번과 거의 동일한 청크
+    engine.add_document(3, vec![0.7, 0.0, 0.7]).unwrap();
+
+    let query_vector = [1.0, 0.0, 0.1];
+    // 일반 검색은 거의 같은 1, 2번을 함께 반환
+    let plain: Vec<u64> = engine.search(&query_vector, 2).unwrap().iter().map(|r| r.0).collect();
+    assert_eq!(plain, vec![1, 2]);
+
+    // MMR은 두 번째 결과로 중복 대신 다른 방향의 3번을 고름
+    let diverse: Vec<u64> = engine.search_mmr(&query_vector, 2, 10, 0.5).unwrap().iter().map(|r| r.0).collect();
+    assert_eq!(diverse, vec![1, 3]);
+
+    // lambda = 1.0이면 일반 검색과 같은 순서
+    let relevance_only: Vec<u64> = engine.search_mmr(&query_vector, 2, 10, 1.0).unwrap().iter().map(|r| r.0).collect();
+    assert_eq!(relevance_only, plain);
+}
+
+#[test]
+fn test_search_mmr_with_nan_query_does_not_panic() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.0, 1.0, 0.0]).unwrap();
+
+    let query_vector = [f32::NAN, 0.0, 0.0];
+    assert_eq!(engine.search_mmr(&query_vector, 2, 10, 0.5).unwrap().len(), 2);
+    assert_eq!(engine.search(&query_vector, 2).unwrap().len(), 2);
+}
+
+#[test]
+fn test_search_by_id_excludes_source_and_group() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.95, 0.05, 0.0]).unwrap();
+    engine.add_document(3, vec![0.8, 0.2, 0.0]).unwrap();
+    engine.set_payload(&1, json!({ "note": "a.md" }).as_object().unwrap().clone()).unwrap();
+    engine.set_payload(&2, json!({ "note": "a.md" }).as_object().unwrap().clone()).unwrap();
+    engine.set_payload(&3, json!({ "note": "b.md" }).as_object().unwrap().clone()).unwrap();
+
+    let related: Vec<u64> = engine.search_by_id(&1, 2, None).unwrap().iter().map(|r| r.0).collect();
+    assert_eq!(related, vec![2, 3]);
+
+    // 같은 노트의 다른 청크(2번)까지 제외
+    let related: Vec<u64> = engine.search_by_id(&1, 2, Some("note")).unwrap().iter().map(|r| r.0).collect();
+    assert_eq!(related, vec![3]);
+
+    let missing = engine.search_by_id(&99, 2, None);
+    assert!(matches!(missing.unwrap_err(), VectorEngineError::ItemNotFound(_)));
+}
+
+#[test]
+fn test_recommend_with_positive_and_negative_examples() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap(); // 긍정 예시
+    engine.add_document(2, vec![0.0, 1.0, 0.0]).unwrap(); // 부정 예시
+    engine.add_document(3, vec![0.7, 0.7, 0.0]).unwrap(); // 둘 다와 비슷
+    engine.add_document(4, vec![0.7, -0.3, 0.3]).unwrap(); // 긍정 쪽, 부정과는 반대
+
+    for strategy in [RecommendStrategy::AverageVector, RecommendStrategy::BestScore] {
+        let results = engine.recommend(&[1], &[2], 2, strategy).unwrap();
+        let ids: Vec<u64> = results.iter().map(|r| r.0).collect();
+        // 예시 문서는 제외되고, 부정 예시와 먼 4번이 먼저
+        assert_eq!(ids, vec![4, 3], "{:?}", strategy);
+    }
+
+    let missing = engine.recommend(&[99], &[], 2, RecommendStrategy::AverageVector);
+    assert!(matches!(missing.unwrap_err(), VectorEngineError::ItemNotFound(_)));
+}
+
+#[test]
+fn test_best_score_recommend_with_euclidean_metric() {
+    let mut engine = VectorEngine::with_metric(TEST_DIM, Metric::Euclidean);
+    engine.add_document(1, vec![0.0, 0.0, 0.0]).unwrap(); // 긍정 예시
+    engine.add_document(2, vec![10.0, 0.0, 0.0]).unwrap(); // 부정 예시
+    engine.add_document(3, vec![2.0, 0.0, 0.0]).unwrap(); // 긍정 쪽
+    engine.add_document(4, vec![8.0, 0.0, 0.0]).unwrap(); // 부정 쪽
+
+    // 거리 방식과 무관하게 긍정 예시 쪽 후보가 먼저 추천됨
+    let results = engine.recommend(&[1], &[2], 2, RecommendStrategy::BestScore).unwrap();
+    assert_eq!(results.iter().map(|r| r.0).collect::<Vec<_>>(), vec![3, 4]);
+    assert!(results[0].1 < 1.0 && results[1].1 > 1.0);
+}
+
+#[test]
+fn test_search_multi_fuses_paraphrase_queries() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.0, 1.0, 0.0]).unwrap();
+    engine.add_document(3, vec![0.7, 0.7, 0.0]).unwrap();
+    engine.add_document(4, vec![0.0, 0.0, 1.0]).unwrap();
+
+    // 두 질의 모두에 적당히 가까운 3번이 융합 결과 최상위
+    let queries = vec![vec![0.9, 0.5, 0.0], vec![0.5, 0.9, 0.0]];
+    for fusion in [FusionMethod::ReciprocalRank, FusionMethod::SumScore] {
+        let results = engine.search_multi(&queries, 3, fusion).unwrap();
+        assert_eq!(results[0].0, 3, "{:?}", fusion);
+        assert!(results.iter().all(|r| r.0 != 4));
+    }
+
+    // MaxScore는 어느 한 질의에 가장 가까운 문서를 우선함
+    let results = engine.search_multi(&[vec![1.0, 0.0, 0.0], vec![0.0, 0.0, 1.0]], 2, FusionMethod::MaxScore).unwrap();
+    let mut ids: Vec<u64> = results.iter().map(|r| r.0).collect();
+    ids.sort();
+    assert_eq!(ids, vec![1, 4]);
+}
+
+#[test]
+fn test_search_groups_returns_best_chunks_per_note() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    let a = engine.add_chunks("a.md", vec![vec![1.0, 0.0, 0.0], vec![0.9, 0.1, 0.0], vec![0.0, 0.0, 1.0]]).unwrap();
+    let b = engine.add_chunks("b.md", vec![vec![0.8, 0.2, 0.0], vec![0.0, 1.0, 0.0]]).unwrap();
+
+    let hits = engine.search_groups(&[1.0, 0.0, 0.0], 2, 2, GroupAggregate::Max).unwrap();
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[0].group, "a.md");
+    assert_eq!(hits[0].chunks.iter().map(|c| c.0).collect::<Vec<_>>(), vec![a[0], a[1]]);
+    assert_eq!(hits[1].group, "b.md");
+    assert_eq!(hits[1].chunks[0].0, b[0]);
+
+    // Mean 점수는 Max 점수보다 크지 않음
+    let mean_hits = engine.search_groups(&[1.0, 0.0, 0.0], 1, 2, GroupAggregate::Mean).unwrap();
+    assert!(mean_hits[0].score <= hits[0].score);
+}
+
+#[test]
+fn test_search_groups_scores_with_euclidean_metric() {
+    let mut engine = VectorEngine::with_metric(TEST_DIM, Metric::Euclidean);
+    engine.add_chunks("near.md", vec![vec![0.0, 0.0, 0.0], vec![1.0, 0.0, 0.0]]).unwrap();
+    engine.add_chunks("far.md", vec![vec![3.0, 0.0, 0.0]]).unwrap();
+
+    // 유클리드 거리도 (0, 1] 범위의 유사도로 채점되어 가까운 그룹이 먼저
+    let hits = engine.search_groups(&[0.0, 0.0, 0.0], 2, 2, GroupAggregate::Mean).unwrap();
+    assert_eq!(hits.iter().map(|h| h.group.as_str()).collect::<Vec<_>>(), vec!["near.md", "far.md"]);
+    assert!((hits[0].score - 0.75).abs() < 1e-6);
+    assert!((hits[1].score - 0.25).abs() < 1e-6);
+}
+
+#[test]
+fn test_search_groups_with_dominant_group() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    // 긴 노트의 청크들이 질의 주변의 후보를 모두 차지하는 경우
+    let long: Vec<Vec<f32>> = (0..40).map(|i| vec![1.0, i as f32 * 0.001, 0.0]).collect();
+    engine.add_chunks("long.md", long).unwrap();
+    let short = engine.add_chunks("short.md", vec![vec![0.0, 1.0, 0.0]]).unwrap();
+
+    let hits = engine.search_groups(&[1.0, 0.0, 0.0], 2, 1, GroupAggregate::Max).unwrap();
+    assert_eq!(hits.iter().map(|h| h.group.as_str()).collect::<Vec<_>>(), vec!["long.md", "short.md"]);
+    assert_eq!(hits[1].chunks[0].0, short[0]);
+}
+
+#[test]
+fn test_delete_group_removes_all_chunks() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_chunks("a.md", vec![vec![1.0, 0.0, 0.0], vec![0.9, 0.1, 0.0]]).unwrap();
+    let b = engine.add_chunks("b.md", vec![vec![0.0, 1.0, 0.0]]).unwrap();
+
+    let reloaded = VectorEngine::load_from_bytes(&engine.save_to_bytes().unwrap(), TEST_DIM).unwrap();
+    assert_eq!(reloaded.group_members("a.md").len(), 2);
+
+    assert_eq!(engine.delete_group("a.md").unwrap(), 2);
+    assert_eq!(engine.document_count(), 1);
+    assert_eq!(engine.search(&[1.0, 0.0, 0.0], 1).unwrap()[0].0, b[0]);
+    assert!(matches!(engine.delete_group("a.md").unwrap_err(), VectorEngineError::ItemNotFound(_)));
+}
+
+#[test]
+fn test_search_maxsim_scores_token_documents() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    // 1번 문서는 두 질의 토큰 모두에 대응하는 토큰을 가짐
+    engine.add_token_document(1, vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]).unwrap();
+    // 2번 문서는 첫 번째 질의 토큰과만 정확히 일치
+    engine.add_token_document(2, vec![vec![1.0, 0.0, 0.0], vec![0.0, 0.0, 1.0]]).unwrap();
+    assert_eq!(engine.document_count(), 0);
+
+    let query = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]];
+    let results = engine.search_maxsim(&query, 2, 4).unwrap();
+    assert_eq!(results.iter().map(|r| r.0).collect::<Vec<_>>(), vec![1, 2]);
+    assert!((results[0].1 - 2.0).abs() < 1e-5);
+
+    let mut reloaded = VectorEngine::load_from_bytes(&engine.save_to_bytes().unwrap(), TEST_DIM).unwrap();
+    assert_eq!(reloaded.token_document(&2), engine.token_document(&2));
+    reloaded.delete_token_document(&1).unwrap();
+    assert_eq!(reloaded.search_maxsim(&query, 2, 4).unwrap()[0].0, 2);
+
+    let res = engine.add_token_document(3, vec![vec![1.0, 0.0]]);
+    assert!(matches!(res.unwrap_err(), VectorEngineError::DimensionMismatch(_)));
+}
+
+#[test]
+fn test_search_maxsim_uses_engine_metric() {
+    let mut engine = VectorEngine::with_metric(TEST_DIM, Metric::Euclidean);
+    engine.add_token_document(1, vec![vec![2.0, 0.0, 0.0]]).unwrap();
+    engine.add_document(1, vec![2.0, 0.0, 0.0]).unwrap();
+
+    // 토큰 하나짜리 질의의 MaxSim 점수는 같은 벡터에 대한 일반 검색의 유사도와 같음
+    let query = [1.0, 0.0, 0.0];
+    let distance = engine.search(&query, 1).unwrap()[0].1;
+    let results = engine.search_maxsim(&[query.to_vec()], 1, 4).unwrap();
+    assert!((results[0].1 - Metric::Euclidean.similarity(distance)).abs() < 1e-6);
+    assert!((results[0].1 - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn test_sparse_search_and_sparse_dense_hybrid() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.8, 0.6, 0.0]).unwrap();
+    engine.add_document(3, vec![0.0, 1.0, 0.0]).unwrap();
+    engine.set_sparse_vector(&1, SparseVector::new(vec![10], vec![0.2]).unwrap()).unwrap();
+    engine.set_sparse_vector(&2, SparseVector::new(vec![10, 42], vec![0.1, 2.0]).unwrap()).unwrap();
+
+    let sparse_query = SparseVector::new(vec![42], vec![1.0]).unwrap();
+    assert_eq!(engine.sparse_search(&sparse_query, 3), vec![(2, 2.0)]);
+
+    let dense_query = [1.0, 0.0, 0.0];
+    assert_eq!(engine.hybrid_sparse_search(&dense_query, &sparse_query, 3, 1.0).unwrap()[0].0, 1);
+    assert_eq!(engine.hybrid_sparse_search(&dense_query, &sparse_query, 3, 0.3).unwrap()[0].0, 2);
+
+    let reloaded = VectorEngine::load_from_bytes(&engine.save_to_bytes().unwrap(), TEST_DIM).unwrap();
+    assert_eq!(reloaded.sparse_vector(&2), engine.sparse_vector(&2));
+    engine.delete_document(&2).unwrap();
+    assert!(engine.sparse_search(&sparse_query, 3).is_empty());
+
+    // 정렬되지 않았거나 중복된 인덱스는 저장 전에 정규화됨
+    let raw = SparseVector { indices: vec![42, 7, 42], values: vec![1.0, 0.5, 1.0] };
+    engine.set_sparse_vector(&3, raw).unwrap();
+    assert_eq!(engine.sparse_vector(&3), SparseVector::new(vec![7, 42], vec![0.5, 2.0]).as_ref());
+}
+
+#[test]
+fn test_named_vector_spaces_have_own_dimension() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.create_vector_space("title", 2, Metric::Euclidean).unwrap();
+    assert!(matches!(
+        engine.create_vector_space("title", 2, Metric::Cosine).unwrap_err(),
+        VectorEngineError::AlreadyExists(_)
+    ));
+
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.0, 1.0, 0.0]).unwrap();
+    engine.set_named_vector(&1, "title", vec![0.0, 0.0]).unwrap();
+    engine.set_named_vector(&2, "title", vec![5.0, 5.0]).unwrap();
+
+    // 차원 검사는 엔진 차원(3)이 아닌 공간 차원(2) 기준
+    let res = engine.set_named_vector(&1, "title", vec![1.0, 0.0, 0.0]);
+    assert!(matches!(res.unwrap_err(), VectorEngineError::DimensionMismatch(_)));
+
+    let results = engine.search_space("title", &[4.0, 4.0], 2).unwrap();
+    assert_eq!(results[0].0, 2);
+    assert!((results[0].1 - 2.0_f32.sqrt()).abs() < 1e-5);
+    assert!(matches!(engine.search_space("body", &[4.0, 4.0], 2).unwrap_err(), VectorEngineError::ItemNotFound(_)));
+
+    // 스냅샷 로딩 후에도 공간 설정과 벡터가 유지되고, 문서 삭제 시 함께 제거됨
+    let mut reloaded = VectorEngine::load_from_bytes(&engine.save_to_bytes().unwrap(), TEST_DIM).unwrap();
+    assert_eq!(reloaded.vector_space("title").unwrap().metric(), Metric::Euclidean);
+    assert_eq!(reloaded.named_vector(&2, "title"), Some(&vec![5.0, 5.0]));
+    reloaded.delete_document(&2).unwrap();
+    assert_eq!(reloaded.search_space("title", &[4.0, 4.0], 2).unwrap()[0].0, 1);
+}
+
+#[test]
+fn test_documents_with_only_named_vectors() {
+    let mut engine = VectorEngine::new(TEST_DIM);
+    engine.create_vector_space("v2", 2, Metric::Cosine).unwrap();
+    engine.add_document(1, vec![1.0, 0.0, 0.0]).unwrap();
+    engine.set_named_vector(&1, "v2", vec![1.0, 0.0]).unwrap();
+
+    // 새 모델의 공간에만 벡터가 있는 문서
+    engine.set_named_vector(&5, "v2", vec![0.0, 1.0]).unwrap();
+    engine.set_payload(&5, json!({ "tag": "new" }).as_object().unwrap().clone()).unwrap();
+    assert!(engine.contains(&5));
+    assert!(engine.get(&5).unwrap().vector.is_empty());
+    assert_eq!(engine.search_space("v2", &[0.0, 1.0], 1).unwrap()[0].0, 5);
+    assert_eq!(engine.search(&[1.0, 0.0, 0.0], 5).unwrap().len(), 1);
+
+    // 이름 있는 벡터만 바뀌면 메인 공간의 쿼리 캐시는 유지됨
+    let hits = engine.query_cache_stats().hits;
+    engine.set_named_vector(&1, "v2", vec![0.5, 0.5]).unwrap();
+    engine.search(&[1.0, 0.0, 0.0], 5).unwrap();
+    assert_eq!(engine.query_cache_stats().hits, hits + 1);
+
+    let mut reloaded = VectorEngine::load_from_bytes(&engine.save_to_bytes().unwrap(), TEST_DIM).unwrap();
+    assert_eq!(reloaded.get(&5), engine.get(&5));
+    assert_eq!(reloaded.named_vector(&5, "v2"), Some(&vec![0.0, 1.0]));
+
+    // 공간을 지우면 그 공간의 벡터만 가진 문서도 사라짐
+    reloaded.drop_vector_space("v2").unwrap();
+    assert!(!reloaded.contains(&5) && reloaded.payload(&5).is_none());
+    assert!(reloaded.contains(&1));
+}
@@ -0,0 +1,197 @@
+use rust_vector_engine::models::document::{PayloadUpdate, WalRecord, wal_record};
+use rust_vector_engine::models::wal::encode_frame;
+use rust_vector_engine::models::{Metric, SharedBuffer, VectorEngine};
+use serde_json::json;
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// 항상 쓰기에 실패하는 sink (디스크가 가득 찬 상황)
+struct FailingSink;
+
+impl Write for FailingSink {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::Error::other("disk full"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_wal_replay_restores_changes_after_snapshot() {
+    let mut engine = VectorEngine::new(2);
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+    let snapshot = engine.save_to_bytes().unwrap();
+
+    let log = SharedBuffer::new();
+    engine.attach_wal(log.clone()).unwrap();
+    engine.add_document(2, vec![0.0, 1.0]).unwrap();
+    let id = engine.add_document_by_key("doc-a", vec![0.5, 0.5]).unwrap();
+    engine.set_payload(&2, json!({ "tag": "news" }).as_object().unwrap().clone()).unwrap();
+    engine.create_vector_space("title", 3, Metric::Cosine).unwrap();
+    engine.set_named_vector(&2, "title", vec![1.0, 0.0, 0.0]).unwrap();
+    engine.set_named_vector(&9, "title", vec![0.0, 1.0, 0.0]).unwrap();
+    engine.delete_document(&1).unwrap();
+    let bytes = log.take();
+
+    let (restored, replay) = VectorEngine::load_with_wal(&snapshot, &bytes, 2).unwrap();
+    assert_eq!(replay.applied as u64, engine.wal_sequence());
+    assert_eq!(replay.skipped, 0);
+    assert_eq!(restored.document_count(), 2);
+    assert!(!restored.contains(&1));
+    assert_eq!(restored.get(&2), engine.get(&2));
+    assert_eq!(restored.named_vector(&9, "title"), engine.named_vector(&9, "title"));
+    assert_eq!(restored.get(&id), engine.get(&id));
+
+    // 재생 후 새로 할당되는 ID가 기존 ID와 겹치지 않음
+    let mut restored = restored;
+    assert_eq!(restored.insert(vec![1.0, 1.0]).unwrap(), engine.insert(vec![1.0, 1.0]).unwrap());
+}
+
+#[test]
+fn test_wal_ignores_torn_tail() {
+    let mut engine = VectorEngine::new(2);
+    let snapshot = engine.save_to_bytes().unwrap();
+    let log = SharedBuffer::new();
+    engine.attach_wal(log.clone()).unwrap();
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.0, 1.0]).unwrap();
+    let mut bytes = log.take();
+    let full_len = bytes.len();
+
+    // 마지막 레코드를 쓰는 도중 중단된 상황
+    bytes.truncate(full_len - 3);
+    let (restored, replay) = VectorEngine::load_with_wal(&snapshot, &bytes, 2).unwrap();
+    assert_eq!(replay.applied, 1);
+    assert_eq!(replay.truncated_bytes, bytes.len() - replay.valid_len);
+    assert!(restored.contains(&1) && !restored.contains(&2));
+
+    // 체크섬이 맞지 않는 레코드부터는 적용하지 않음
+    let mut corrupted = log.take();
+    corrupted.extend_from_slice(&bytes[..replay.valid_len]);
+    let last = corrupted.len() - 1;
+    corrupted[last] ^= 0xFF;
+    let (restored, replay) = VectorEngine::load_with_wal(&snapshot, &corrupted, 2).unwrap();
+    assert_eq!(replay.applied, 0);
+    assert_eq!(restored.document_count(), 0);
+}
+
+#[test]
+fn test_checkpoint_skips_records_already_in_snapshot() {
+    let mut engine = VectorEngine::new(2);
+    let old_log = SharedBuffer::new();
+    engine.attach_wal(old_log.clone()).unwrap();
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+    engine.add_document(2, vec![0.0, 1.0]).unwrap();
+
+    let new_log = SharedBuffer::new();
+    let snapshot = engine.checkpoint(new_log.clone()).unwrap();
+    engine.update_document(&2, vec![1.0, 1.0]).unwrap();
+
+    // 이전 로그를 지우기 전에 중단되어 두 로그가 모두 남은 경우
+    let mut bytes = old_log.take();
+    bytes.extend(new_log.take());
+    let (restored, replay) = VectorEngine::load_with_wal(&snapshot, &bytes, 2).unwrap();
+    assert_eq!(replay.skipped, 2);
+    assert_eq!(replay.applied, 1);
+    assert_eq!(restored.wal_sequence(), 3);
+    assert_eq!(restored.get(&2), engine.get(&2));
+}
+
+#[test]
+fn test_failed_append_rolls_back_changes() {
+    let mut engine = VectorEngine::new(2);
+    engine.add_document_by_key("a", vec![1.0, 0.0]).unwrap();
+    let b = engine.add_document_by_key("b", vec![0.0, 1.0]).unwrap();
+    engine.attach_wal(FailingSink).unwrap();
+
+    assert!(engine.insert(vec![0.5, 0.5]).is_err());
+    assert!(engine.update_document(&b, vec![1.0, 1.0]).is_err());
+    assert!(engine.rename_document_replacing("a", "b").is_err());
+    assert!(engine.create_vector_space("title", 3, Metric::Cosine).is_err());
+    assert_eq!(engine.wal_sequence(), 0);
+
+    // 기록되지 않은 변경은 메모리에도 남지 않음
+    assert_eq!(engine.document_count(), 2);
+    assert_eq!(engine.get(&b).unwrap().vector, vec![0.0, 1.0]);
+    assert_eq!(engine.document_key(&b), Some("b"));
+    assert!(engine.document_id("a").is_some());
+    assert!(engine.vector_space("title").is_none());
+    assert_eq!(engine.search(&[0.0, 1.0], 1).unwrap()[0].0, b);
+
+    // 새 로그로 교체하면 다시 기록됨
+    let log = SharedBuffer::new();
+    engine.checkpoint(log.clone()).unwrap();
+    let id = engine.insert(vec![0.5, 0.5]).unwrap();
+    assert!(engine.contains(&id));
+    assert_eq!(engine.wal_sequence(), 1);
+}
+
+#[test]
+fn test_wal_replay_rebuilds_index_when_a_record_fails() {
+    let mut engine = VectorEngine::new(2);
+    let snapshot = engine.save_to_bytes().unwrap();
+    let log = SharedBuffer::new();
+    engine.attach_wal(log.clone()).unwrap();
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+    let mut bytes = log.take();
+    engine.create_vector_space("title", 3, Metric::Cosine).unwrap();
+    log.take();
+    engine.set_named_vector(&1, "title", vec![1.0, 0.0, 0.0]).unwrap();
+    bytes.extend(log.take());
+
+    // 공간 생성 레코드가 빠져 마지막 레코드를 반영할 수 없는 로그
+    let mut restored = VectorEngine::load_from_bytes(&snapshot, 2).unwrap();
+    assert!(restored.replay_wal(&bytes).is_err());
+    assert_eq!(restored.wal_sequence(), 1);
+    assert_eq!(restored.search(&[1.0, 0.0], 1).unwrap()[0].0, 1);
+}
+
+/// flush 횟수를 세는 sink
+struct CountingSink(Arc<AtomicUsize>);
+
+impl Write for CountingSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_attach_wal_flushes_replaced_sink() {
+    let mut engine = VectorEngine::new(2);
+    let flushes = Arc::new(AtomicUsize::new(0));
+    engine.attach_wal(CountingSink(flushes.clone())).unwrap();
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+    let before = flushes.load(Ordering::SeqCst);
+    engine.attach_wal(SharedBuffer::new()).unwrap();
+    assert_eq!(flushes.load(Ordering::SeqCst), before + 1);
+}
+
+#[test]
+fn test_wal_replay_skips_payload_for_missing_document() {
+    let mut engine = VectorEngine::new(2);
+    let snapshot = engine.save_to_bytes().unwrap();
+    let log = SharedBuffer::new();
+    engine.attach_wal(log.clone()).unwrap();
+    engine.add_document(1, vec![1.0, 0.0]).unwrap();
+    let mut bytes = log.take();
+    let orphan = WalRecord {
+        sequence: 2,
+        next_id: 0,
+        op: Some(wal_record::Op::Payload(PayloadUpdate { id: 42, payload: r#"{"tag":"lost"}"#.to_string() })),
+    };
+    bytes.extend(encode_frame(&orphan));
+
+    // 문서 없는 메타데이터는 복원되지 않음
+    let (restored, replay) = VectorEngine::load_with_wal(&snapshot, &bytes, 2).unwrap();
+    assert_eq!(replay.truncated_bytes, 0);
+    assert!(restored.contains(&1));
+    assert!(!restored.contains(&42) && restored.payload(&42).is_none());
+}